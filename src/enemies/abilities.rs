@@ -0,0 +1,304 @@
+//! Abilities make enemy types play differently. They are listed per enemy in the enemy
+//! definitions and become components when the enemy spawns, so every ability is handled by its
+//! own system, or by the shots when damage is applied.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::tower_building::Difficulty;
+
+use super::*;
+
+/// Life multiplier of the enemies spawned when another one splits on death
+pub const SPLIT_HEALTH_MULTIPLIER: f32 = 0.5;
+/// Distance between the enemies spawned by a split or a summon, so they don't overlap
+pub const MINION_SPAWN_OFFSET: f32 = 12.0;
+
+/// Life, shield and healing values are the ones of the first wave, they scale with the waves
+/// like the enemy life does.
+#[derive(Debug, Clone, Deserialize)]
+pub enum EnemyAbility {
+    /// Flat damage reduction for every shot
+    Armor(u16),
+    Regeneration {
+        amount: u16,
+        interval: f32,
+    },
+    /// Absorbs damage before the life
+    Shield(u16),
+    SplitOnDeath {
+        enemy: String,
+        count: u8,
+    },
+    /// Heals the other enemies in range
+    HealAura {
+        radius: f32,
+        amount: u16,
+        interval: f32,
+    },
+}
+
+impl EnemyAbility {
+    /// Short description with the values the ability has for the given life scale
+    pub fn describe(&self, life_scale: f32) -> String {
+        let scaled = |value: u16| (value as f32 * life_scale).round() as u16;
+
+        match self {
+            EnemyAbility::Armor(armor) => format!("armor {}", armor),
+            EnemyAbility::Regeneration { amount, interval } => {
+                format!("regenerates {} every {}s", scaled(*amount), interval)
+            }
+            EnemyAbility::Shield(shield) => format!("shield {}", scaled(*shield)),
+            EnemyAbility::SplitOnDeath { enemy, count } => {
+                format!("splits into {} {}", count, enemy)
+            }
+            EnemyAbility::HealAura { amount, .. } => {
+                format!("heals {} to nearby enemies", scaled(*amount))
+            }
+        }
+    }
+}
+
+#[derive(Component, Debug, Deref)]
+pub struct Armor(pub u16);
+
+#[derive(Component, Debug)]
+pub struct Regeneration {
+    pub amount: u16,
+    pub timer: Timer,
+}
+
+#[derive(Component, Debug, Deref, DerefMut)]
+pub struct Shield(pub u16);
+
+#[derive(Component, Debug)]
+pub struct SplitOnDeath {
+    pub enemy: String,
+    pub count: u8,
+}
+
+#[derive(Component, Debug)]
+pub struct HealAura {
+    pub radius: f32,
+    pub amount: u16,
+    pub timer: Timer,
+}
+
+pub fn insert_abilities(enemy: &mut EntityCommands, abilities: &[EnemyAbility], life_scale: f32) {
+    let scaled = |value: u16| (value as f32 * life_scale).round() as u16;
+
+    for ability in abilities {
+        match ability {
+            EnemyAbility::Armor(armor) => {
+                enemy.insert(Armor(*armor));
+            }
+            EnemyAbility::Regeneration { amount, interval } => {
+                enemy.insert(Regeneration {
+                    amount: scaled(*amount),
+                    timer: Timer::from_seconds(*interval, TimerMode::Repeating),
+                });
+            }
+            EnemyAbility::Shield(shield) => {
+                enemy.insert(Shield(scaled(*shield)));
+            }
+            EnemyAbility::SplitOnDeath { enemy: id, count } => {
+                enemy.insert(SplitOnDeath {
+                    enemy: id.clone(),
+                    count: *count,
+                });
+            }
+            EnemyAbility::HealAura {
+                radius,
+                amount,
+                interval,
+            } => {
+                enemy.insert(HealAura {
+                    radius: *radius,
+                    amount: scaled(*amount),
+                    timer: Timer::from_seconds(*interval, TimerMode::Repeating),
+                });
+            }
+        }
+    }
+}
+
+/// Result of a shot landing on an enemy
+#[derive(Debug, Clone, Copy)]
+pub struct Hit {
+    /// Damage taken by the shield and the life
+    pub damage: u16,
+    /// Whether this hit killed the enemy, a dead enemy takes no more damage until it's rewarded
+    /// and despawned
+    pub killed: bool,
+}
+
+/// Applies the damage of a shot to an enemy, the armor reduces it (a hit always deals at least
+/// 1 damage) and the shield takes it before the life.
+pub fn apply_damage(
+    enemy: &mut Enemy,
+    damage: u16,
+    armor: u16,
+    shield: Option<&mut Shield>,
+) -> Hit {
+    if enemy.life == 0 {
+        return Hit {
+            damage: 0,
+            killed: false,
+        };
+    }
+    let mut damage = if armor > 0 {
+        damage.saturating_sub(armor).max(1)
+    } else {
+        damage
+    };
+
+    let mut absorbed = 0;
+    if let Some(shield) = shield {
+        absorbed = damage.min(shield.0);
+        shield.0 -= absorbed;
+        damage -= absorbed;
+    }
+
+    let life_damage = damage.min(enemy.life);
+    enemy.life -= life_damage;
+    Hit {
+        damage: absorbed + life_damage,
+        killed: enemy.life == 0,
+    }
+}
+
+/// Spawns enemies around a position, used by splits and boss summons
+pub fn spawn_minions(
+    commands: &mut Commands,
+    enemy: &str,
+    count: u8,
+    health_multiplier: f32,
+    translation: Vec3,
+    path_follower: PathFollower,
+    wave_control: &WaveControl,
+    difficulty: &Difficulty,
+) {
+    let Some(enemy_type) = wave_control.enemy_type(enemy) else {
+        warn!("unknown enemy to spawn: {}", enemy);
+        return;
+    };
+
+    for i in 0..count {
+        let offset = (i as f32 - (count - 1) as f32 / 2.0) * MINION_SPAWN_OFFSET;
+        spawn_enemy(
+            commands,
+            enemy_type,
+            wave_control.wave_count,
+            health_multiplier,
+            translation + Vec3::new(offset, 0.0, 0.0),
+            // every minion takes its own branches at the next forks
+            PathFollower {
+                fork_seed: path_follower.fork_seed.wrapping_add(i as u64 + 1),
+                ..path_follower
+            },
+            difficulty,
+        );
+    }
+}
+
+pub fn regenerate_enemies(
+    mut enemies: Query<(&mut Enemy, &mut Regeneration), Without<Dying>>,
+    time: Res<Time>,
+) {
+    for (mut enemy, mut regeneration) in &mut enemies {
+        regeneration.timer.tick(time.delta());
+        // killed enemies stay dead until they are rewarded and despawned
+        if regeneration.timer.just_finished() && enemy.life > 0 {
+            enemy.life = enemy
+                .life
+                .saturating_add(regeneration.amount)
+                .min(enemy.max_life);
+        }
+    }
+}
+
+pub fn heal_nearby_enemies(
+    mut healers: Query<(Entity, &Transform, &mut HealAura), Without<Dying>>,
+    mut enemies: Query<(Entity, &Transform, &mut Enemy)>,
+    time: Res<Time>,
+) {
+    for (healer_entity, healer_transform, mut aura) in &mut healers {
+        aura.timer.tick(time.delta());
+        if !aura.timer.just_finished() {
+            continue;
+        }
+
+        let radius_squared = aura.radius * aura.radius;
+        for (entity, transform, mut enemy) in &mut enemies {
+            let in_range = transform
+                .translation
+                .distance_squared(healer_transform.translation)
+                <= radius_squared;
+            if entity != healer_entity && in_range && enemy.life > 0 {
+                enemy.life = enemy.life.saturating_add(aura.amount).min(enemy.max_life);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enemy(life: u16) -> Enemy {
+        Enemy {
+            id: "test".to_string(),
+            life,
+            max_life: life,
+            speed: 1.0,
+            bounty: 0,
+            damage_to_base: 1,
+        }
+    }
+
+    #[test]
+    fn shield_absorbs_the_damage_first() {
+        let mut enemy = enemy(10);
+        let mut shield = Shield(8);
+        let hit = apply_damage(&mut enemy, 5, 0, Some(&mut shield));
+
+        assert_eq!(hit.damage, 5);
+        assert!(!hit.killed);
+        assert_eq!(shield.0, 3);
+        assert_eq!(enemy.life, 10);
+    }
+
+    #[test]
+    fn damage_over_the_shield_goes_to_the_life() {
+        let mut enemy = enemy(10);
+        let mut shield = Shield(3);
+        let hit = apply_damage(&mut enemy, 8, 0, Some(&mut shield));
+
+        assert_eq!(hit.damage, 8);
+        assert_eq!(shield.0, 0);
+        assert_eq!(enemy.life, 5);
+    }
+
+    #[test]
+    fn armor_above_the_damage_still_deals_one() {
+        let mut enemy = enemy(10);
+        let hit = apply_damage(&mut enemy, 4, 6, None);
+
+        assert_eq!(hit.damage, 1);
+        assert_eq!(enemy.life, 9);
+    }
+
+    #[test]
+    fn killing_hit_is_flagged_once() {
+        let mut enemy = enemy(10);
+        let hit = apply_damage(&mut enemy, 25, 0, None);
+        assert_eq!(hit.damage, 10);
+        assert!(hit.killed);
+        assert_eq!(enemy.life, 0);
+
+        // the enemy is already dead, the next shots don't count
+        let hit = apply_damage(&mut enemy, 25, 0, None);
+        assert_eq!(hit.damage, 0);
+        assert!(!hit.killed);
+    }
+}
@@ -1,6 +1,13 @@
+//! Enemies play the walk animation of the direction they move in. Sprite sheets only need the up,
+//! down and left frames: the missing directions fall back to a close one, mirrored when needed,
+//! so enemies look right on any path layout. Hit and death animations are optional too.
+
 use bevy::prelude::*;
 
-use super::ideal_time_per_frame;
+use super::{ideal_time_per_frame, HitFlash, StatusEffects};
+
+/// Below this, a component of a direction doesn't count, so a diagonal is between 22.5° and 67.5°
+const DIAGONAL_THRESHOLD: f32 = 0.383;
 
 #[derive(Clone, Debug)]
 pub struct AnimateSprite {
@@ -24,7 +31,15 @@ pub struct EnemyAnimation {
     pub walk_up: AnimateSprite,
     pub walk_down: AnimateSprite,
     pub walk_left: AnimateSprite,
+    pub walk_right: Option<AnimateSprite>,
+    pub walk_up_left: Option<AnimateSprite>,
+    pub walk_up_right: Option<AnimateSprite>,
+    pub walk_down_left: Option<AnimateSprite>,
+    pub walk_down_right: Option<AnimateSprite>,
+    pub hit: Option<AnimateSprite>,
+    pub death: Option<AnimateSprite>,
     pub state: EnemyAnimationState,
+    /// Whether the left frames look to the right, so they are mirrored
     pub need_flip: bool,
 }
 
@@ -34,52 +49,193 @@ impl Default for EnemyAnimation {
             walk_up: Default::default(),
             walk_down: Default::default(),
             walk_left: Default::default(),
+            walk_right: None,
+            walk_up_left: None,
+            walk_up_right: None,
+            walk_down_left: None,
+            walk_down_right: None,
+            hit: None,
+            death: None,
             state: EnemyAnimationState::WalkLeft,
             need_flip: false,
         }
     }
 }
 
-impl EnemyAnimation {
-    pub fn make_all(first: usize, last: usize, timer: Timer) -> Self {
-        let animate_sprite = || AnimateSprite {
-            first,
-            last,
-            timer: timer.clone(),
-        };
-        Self {
-            walk_up: animate_sprite(),
-            walk_down: animate_sprite(),
-            walk_left: animate_sprite(),
-            ..default()
-        }
-    }
-}
-
-#[derive(PartialEq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum EnemyAnimationState {
     WalkUp,
     WalkDown,
     WalkLeft,
+    WalkRight,
+    WalkUpLeft,
+    WalkUpRight,
+    WalkDownLeft,
+    WalkDownRight,
+    Hit,
+    Death,
 }
 
+impl EnemyAnimationState {
+    /// Walk state of a movement direction, in 8 directions
+    pub fn from_direction(direction: Vec2) -> Self {
+        let direction = direction.normalize_or_zero();
+        let horizontal = direction.x.abs() >= DIAGONAL_THRESHOLD;
+        let vertical = direction.y.abs() >= DIAGONAL_THRESHOLD;
+        let (left, down) = (direction.x < 0.0, direction.y < 0.0);
+
+        match (horizontal, vertical) {
+            (true, true) => match (left, down) {
+                (true, true) => EnemyAnimationState::WalkDownLeft,
+                (false, true) => EnemyAnimationState::WalkDownRight,
+                (true, false) => EnemyAnimationState::WalkUpLeft,
+                (false, false) => EnemyAnimationState::WalkUpRight,
+            },
+            (false, true) if down => EnemyAnimationState::WalkDown,
+            (false, true) => EnemyAnimationState::WalkUp,
+            _ if left => EnemyAnimationState::WalkLeft,
+            _ => EnemyAnimationState::WalkRight,
+        }
+    }
+
+    /// The same direction, looking to the other side
+    fn mirrored(&self) -> Self {
+        match self {
+            EnemyAnimationState::WalkLeft => EnemyAnimationState::WalkRight,
+            EnemyAnimationState::WalkRight => EnemyAnimationState::WalkLeft,
+            EnemyAnimationState::WalkUpLeft => EnemyAnimationState::WalkUpRight,
+            EnemyAnimationState::WalkUpRight => EnemyAnimationState::WalkUpLeft,
+            EnemyAnimationState::WalkDownLeft => EnemyAnimationState::WalkDownRight,
+            EnemyAnimationState::WalkDownRight => EnemyAnimationState::WalkDownLeft,
+            state => *state,
+        }
+    }
+
+    /// Close direction used when a diagonal has no frames
+    fn horizontal(&self) -> Self {
+        match self {
+            EnemyAnimationState::WalkUpLeft | EnemyAnimationState::WalkDownLeft => {
+                EnemyAnimationState::WalkLeft
+            }
+            EnemyAnimationState::WalkUpRight | EnemyAnimationState::WalkDownRight => {
+                EnemyAnimationState::WalkRight
+            }
+            state => *state,
+        }
+    }
+
+    /// Hit and death play over the walk, keeping the side the enemy looks to
+    fn is_walk(&self) -> bool {
+        !matches!(self, EnemyAnimationState::Hit | EnemyAnimationState::Death)
+    }
+
+    fn looks_left(&self) -> bool {
+        matches!(
+            self,
+            EnemyAnimationState::WalkLeft
+                | EnemyAnimationState::WalkUpLeft
+                | EnemyAnimationState::WalkDownLeft
+        )
+    }
+}
+
+impl EnemyAnimation {
+    fn frames(&self, state: EnemyAnimationState) -> Option<&AnimateSprite> {
+        match state {
+            EnemyAnimationState::WalkUp => Some(&self.walk_up),
+            EnemyAnimationState::WalkDown => Some(&self.walk_down),
+            EnemyAnimationState::WalkLeft => Some(&self.walk_left),
+            EnemyAnimationState::WalkRight => self.walk_right.as_ref(),
+            EnemyAnimationState::WalkUpLeft => self.walk_up_left.as_ref(),
+            EnemyAnimationState::WalkUpRight => self.walk_up_right.as_ref(),
+            EnemyAnimationState::WalkDownLeft => self.walk_down_left.as_ref(),
+            EnemyAnimationState::WalkDownRight => self.walk_down_right.as_ref(),
+            EnemyAnimationState::Hit => self.hit.as_ref(),
+            EnemyAnimationState::Death => self.death.as_ref(),
+        }
+    }
+
+    fn frames_mut(&mut self, state: EnemyAnimationState) -> Option<&mut AnimateSprite> {
+        match state {
+            EnemyAnimationState::WalkUp => Some(&mut self.walk_up),
+            EnemyAnimationState::WalkDown => Some(&mut self.walk_down),
+            EnemyAnimationState::WalkLeft => Some(&mut self.walk_left),
+            EnemyAnimationState::WalkRight => self.walk_right.as_mut(),
+            EnemyAnimationState::WalkUpLeft => self.walk_up_left.as_mut(),
+            EnemyAnimationState::WalkUpRight => self.walk_up_right.as_mut(),
+            EnemyAnimationState::WalkDownLeft => self.walk_down_left.as_mut(),
+            EnemyAnimationState::WalkDownRight => self.walk_down_right.as_mut(),
+            EnemyAnimationState::Hit => self.hit.as_mut(),
+            EnemyAnimationState::Death => self.death.as_mut(),
+        }
+    }
+
+    /// Whether the sprite sheet has its own frames for the state, hit and death don't fall back
+    pub fn has_frames(&self, state: EnemyAnimationState) -> bool {
+        self.frames(state).is_some()
+    }
+
+    /// State whose frames are played for `state` and whether they are mirrored. A direction
+    /// without frames uses the other side mirrored, and a diagonal the horizontal direction.
+    pub fn resolve(&self, state: EnemyAnimationState) -> (EnemyAnimationState, bool) {
+        let candidates = [
+            (state, false),
+            (state.mirrored(), true),
+            (state.horizontal(), false),
+            (state.horizontal().mirrored(), true),
+        ];
+        candidates
+            .into_iter()
+            .find(|(candidate, _)| self.has_frames(*candidate))
+            .unwrap_or((EnemyAnimationState::WalkLeft, false))
+    }
+}
+
+/// Plays the frames of the animation state and flips the sprite when they are mirrored, or when
+/// the left frames of the sheet look to the right. Enemies with hit frames play them while they
+/// flash, and the death frames are played once.
 pub fn animate(
-    mut enemy_animation_query: Query<(&mut Transform, &mut Sprite, &mut EnemyAnimation)>,
+    mut enemy_animation_query: Query<(
+        &mut Sprite,
+        &mut EnemyAnimation,
+        Option<&StatusEffects>,
+        Has<HitFlash>,
+    )>,
     time: Res<Time>,
 ) {
-    for (mut _transform, mut enemy_sprite, mut enemy_animation) in &mut enemy_animation_query {
-        let animation = match enemy_animation.state {
-            EnemyAnimationState::WalkUp => &mut enemy_animation.walk_up,
-            EnemyAnimationState::WalkDown => &mut enemy_animation.walk_down,
-            EnemyAnimationState::WalkLeft => &mut enemy_animation.walk_left,
+    for (mut enemy_sprite, mut enemy_animation, status_effects, hit_flash) in
+        &mut enemy_animation_query
+    {
+        let current = if hit_flash && enemy_animation.has_frames(EnemyAnimationState::Hit) {
+            EnemyAnimationState::Hit
+        } else {
+            enemy_animation.state
+        };
+        let (state, mirrored) = enemy_animation.resolve(current);
+        // slowed enemies walk slower, stunned ones stop
+        let speed_multiplier = if state.is_walk() {
+            enemy_sprite.flip_x = mirrored ^ (state.looks_left() && enemy_animation.need_flip);
+            status_effects.map_or(1.0, |effects| effects.speed_multiplier())
+        } else {
+            1.0
         };
 
-        animation.timer.tick(time.delta());
+        let Some(animation) = enemy_animation.frames_mut(state) else {
+            continue;
+        };
+        animation.timer.tick(time.delta().mul_f32(speed_multiplier));
 
         if animation.timer.just_finished() {
             if let Some(atlas) = &mut enemy_sprite.texture_atlas {
-                atlas.index = if atlas.index < animation.first || atlas.index >= animation.last {
+                atlas.index = if atlas.index < animation.first || atlas.index > animation.last {
                     animation.first
+                } else if atlas.index == animation.last {
+                    // the death frames don't loop, the last one stays until the despawn
+                    if state == EnemyAnimationState::Death {
+                        animation.last
+                    } else {
+                        animation.first
+                    }
                 } else {
                     atlas.index + 1
                 };
@@ -87,3 +243,113 @@ pub fn animate(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn straight_directions() {
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::X),
+            EnemyAnimationState::WalkRight
+        );
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::NEG_X),
+            EnemyAnimationState::WalkLeft
+        );
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::Y),
+            EnemyAnimationState::WalkUp
+        );
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::NEG_Y),
+            EnemyAnimationState::WalkDown
+        );
+        // too close to the horizontal to be a diagonal
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::new(-1.0, 0.2)),
+            EnemyAnimationState::WalkLeft
+        );
+    }
+
+    #[test]
+    fn diagonal_directions() {
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::new(1.0, 1.0)),
+            EnemyAnimationState::WalkUpRight
+        );
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::new(-3.0, 2.0)),
+            EnemyAnimationState::WalkUpLeft
+        );
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::new(2.0, -3.0)),
+            EnemyAnimationState::WalkDownRight
+        );
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::new(-0.5, -0.5)),
+            EnemyAnimationState::WalkDownLeft
+        );
+    }
+
+    #[test]
+    fn zero_direction_walks_right() {
+        // `move_enemies` keeps the last state instead, a zero direction only means it didn't move
+        assert_eq!(
+            EnemyAnimationState::from_direction(Vec2::ZERO),
+            EnemyAnimationState::WalkRight
+        );
+    }
+
+    #[test]
+    fn missing_directions_fall_back_to_the_left_frames() {
+        let animation = EnemyAnimation::default();
+        assert_eq!(
+            animation.resolve(EnemyAnimationState::WalkLeft),
+            (EnemyAnimationState::WalkLeft, false)
+        );
+        assert_eq!(
+            animation.resolve(EnemyAnimationState::WalkRight),
+            (EnemyAnimationState::WalkLeft, true)
+        );
+        assert_eq!(
+            animation.resolve(EnemyAnimationState::WalkUpRight),
+            (EnemyAnimationState::WalkLeft, true)
+        );
+        assert_eq!(
+            animation.resolve(EnemyAnimationState::WalkDownLeft),
+            (EnemyAnimationState::WalkLeft, false)
+        );
+        // hit frames are optional, without them the enemy keeps walking
+        assert_eq!(
+            animation.resolve(EnemyAnimationState::Hit),
+            (EnemyAnimationState::WalkLeft, false)
+        );
+    }
+
+    #[test]
+    fn diagonals_use_the_closest_frames() {
+        let animation = EnemyAnimation {
+            walk_right: Some(AnimateSprite::default()),
+            walk_up_left: Some(AnimateSprite::default()),
+            ..default()
+        };
+        assert_eq!(
+            animation.resolve(EnemyAnimationState::WalkUpLeft),
+            (EnemyAnimationState::WalkUpLeft, false)
+        );
+        assert_eq!(
+            animation.resolve(EnemyAnimationState::WalkUpRight),
+            (EnemyAnimationState::WalkUpLeft, true)
+        );
+        assert_eq!(
+            animation.resolve(EnemyAnimationState::WalkDownRight),
+            (EnemyAnimationState::WalkRight, false)
+        );
+        assert_eq!(
+            animation.resolve(EnemyAnimationState::WalkDownLeft),
+            (EnemyAnimationState::WalkLeft, false)
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Bosses are enemy types with a `boss` entry in the enemy definitions. They go through phases
+//! as they lose life, and usually have a high damage to the base if they reach the goal.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::tower_building::Difficulty;
+
+use super::*;
+
+fn default_speed_multiplier() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Summon {
+    /// `id` of the enemy summoned
+    pub enemy: String,
+    pub count: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BossPhase {
+    /// The phase starts when the life goes below this fraction of the max life
+    pub below_life: f32,
+    #[serde(default = "default_speed_multiplier")]
+    pub speed_multiplier: f32,
+    #[serde(default)]
+    pub summon: Option<Summon>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BossDefinition {
+    /// Name shown over the boss health bar
+    pub name: String,
+    /// Phases sorted from the highest `below_life` to the lowest
+    #[serde(default)]
+    pub phases: Vec<BossPhase>,
+}
+
+#[derive(Component, Debug)]
+pub struct Boss {
+    pub definition: BossDefinition,
+    /// Amount of phases already started
+    pub phase: usize,
+}
+
+impl Boss {
+    pub fn new(definition: BossDefinition) -> Self {
+        Self {
+            definition,
+            phase: 0,
+        }
+    }
+}
+
+/// Starts the next phase of every boss whose life went below the phase threshold
+pub fn update_boss_phases(
+    mut commands: Commands,
+    mut bosses: Query<(&Transform, &PathFollower, &mut Enemy, &mut Boss), Without<Dying>>,
+    wave_control: Res<WaveControl>,
+    difficulty: Res<Difficulty>,
+) {
+    for (transform, path_follower, mut enemy, mut boss) in &mut bosses {
+        // killed this frame, it dies before reaching the next phase
+        if enemy.life == 0 {
+            continue;
+        }
+        let life_fraction = enemy.life as f32 / enemy.max_life.max(1) as f32;
+
+        while let Some(phase) = boss.definition.phases.get(boss.phase).cloned() {
+            if life_fraction >= phase.below_life {
+                break;
+            }
+            boss.phase += 1;
+            info!("{} entered phase {}", boss.definition.name, boss.phase);
+
+            enemy.speed *= phase.speed_multiplier;
+            if let Some(summon) = &phase.summon {
+                spawn_minions(
+                    &mut commands,
+                    &summon.enemy,
+                    summon.count,
+                    1.0,
+                    transform.translation,
+                    *path_follower,
+                    &wave_control,
+                    &difficulty,
+                );
+            }
+        }
+    }
+}
@@ -13,36 +13,106 @@ pub struct EnemiesPlugin;
 
 impl Plugin for EnemiesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, load_enemy_sprites)
+        app.init_asset::<EnemyDefinitions>()
+            .register_asset_loader(RonAssetLoader::<EnemyDefinitions>::new(
+                ENEMY_DEFINITIONS_EXTENSIONS,
+            ))
+            .insert_resource(WaveControl::default())
+            .init_asset::<MapDefinition>()
+            .register_asset_loader(RonAssetLoader::<MapDefinition>::new(MAP_EXTENSIONS))
+            .insert_resource(EnemyPaths::default())
+            .insert_resource(GameMode::default())
+            .init_asset::<WaveScript>()
+            .register_asset_loader(RonAssetLoader::<WaveScript>::new(WAVE_SCRIPT_EXTENSIONS))
+            .add_systems(
+                Startup,
+                (
+                    load_enemy_definitions,
+                    load_wave_script,
+                    load_map,
+                    spawn_lead_enemy_marker,
+                ),
+            )
+            .add_systems(
+                Update,
+                (apply_enemy_definitions, apply_wave_script, apply_map),
+            )
+            .add_systems(
+                Update,
+                (
+                    spawn_health_bars,
+                    update_health_bars,
+                    update_lead_enemy_marker,
+                ),
+            )
+            .add_systems(
+                Update,
+                select_game_mode.run_if(
+                    in_state(GameState::Start)
+                        .or(in_state(GameState::GameOver))
+                        .or(in_state(GameState::Victory)),
+                ),
+            )
             .add_systems(
                 Update,
-                (spawn_wave, animate, move_enemies, game_over)
+                (
+                    spawn_wave,
+                    move_enemies,
+                    game_over,
+                    regenerate_enemies,
+                    heal_nearby_enemies,
+                    update_boss_phases,
+                    mark_lead_enemy.after(move_enemies),
+                    update_status_effects,
+                    tint_enemies_with_status_effects.after(update_status_effects),
+                )
                     .run_if(in_state(GameState::Attacking)),
             )
+            .add_systems(
+                Update,
+                call_next_wave_early
+                    .before(wave_control)
+                    .run_if(in_state(GameState::Building)),
+            )
             .add_systems(
                 Update,
                 wave_control
                     .after(spawn_wave)
                     .run_if(in_state(GameState::Building).or(in_state(GameState::Attacking))),
             )
+            // the last killed enemies of a wave finish dying after it's cleared
+            .add_systems(
+                Update,
+                (animate, update_dying_enemies, update_hit_flashes)
+                    .run_if(in_state(GameState::Building).or(in_state(GameState::Attacking))),
+            )
             .add_systems(
                 OnEnter(GameState::GameOver),
                 (
                     despawn_all_enemies_in_game_over,
                     reset_wave_control_on_game_over,
+                    reset_forks_on_game_over,
                 )
                     .run_if(in_state(GameState::GameOver)),
+            )
+            .add_systems(
+                OnEnter(GameState::Victory),
+                (
+                    despawn_all_enemies_in_game_over,
+                    reset_wave_control_on_game_over,
+                    reset_forks_on_game_over,
+                ),
             );
     }
 }
 
-pub const MAX_ENEMIES_PER_WAVE: u8 = 25;
-pub const SPAWN_Y_LOCATION: f32 = 80.0;
-pub const SPAWN_X_LOCATION: f32 = 610.0;
 pub const TIME_BETWEEN_WAVES: f32 = 15.0;
-pub const TIME_BETWEEN_SPAWNS: f32 = 1.5;
-pub const INITIAL_ENEMY_LIFE: u16 = 60;
 pub const SCALAR: f32 = 0.75;
+/// Bonus gold per second skipped when the next wave is called early
+pub const EARLY_CALL_GOLD_PER_SEC: f32 = 2.0;
+pub const ENDLESS_ENEMIES_PER_WAVE: u16 = 25;
+pub const ENDLESS_SPAWN_INTERVAL: f32 = 1.5;
+pub const ENDLESS_MIN_SPAWN_INTERVAL: f32 = 0.5;
 pub const SCALE: f32 = 2.0;
 
 /// Controls enemy waves, including spawn timing, textures, animations, and wave progression.
@@ -52,78 +122,173 @@ pub struct WaveControl {
     /// Current wave number.
     pub wave_count: u8,
 
-    /// Timer controlling the interval between enemy spawns within a wave.
+    /// Timer until the next spawn of the current group, it holds the group delay or interval.
     pub time_between_spawns: Timer,
 
-    /// List of enemy textures and their corresponding texture atlas layouts.
-    pub textures: Vec<(Handle<Image>, Handle<TextureAtlasLayout>)>,
+    /// Enemies built from the enemy definitions asset.
+    pub enemy_types: Vec<EnemyType>,
+
+    /// Waves from the wave script asset.
+    pub waves: Vec<WaveDefinition>,
 
-    /// Animations assigned to enemies.
-    pub animations: Vec<EnemyAnimation>,
+    /// Wave generated from the enemy pool once the scripted waves are over, only in endless mode.
+    pub endless_wave: Option<WaveDefinition>,
 
-    /// Number of enemies spawned in the current wave.
-    pub spawned_count_in_wave: u8,
+    /// Spawn group of the current wave being spawned.
+    pub group_index: usize,
+
+    /// Number of enemies spawned in the current group.
+    pub spawned_in_group: u16,
 
     /// Timer controlling the interval between waves.
     pub time_between_waves: Timer,
 
     /// Value to control wether first wave needs to be spawned or not
     pub first_wave_spawned: bool,
+
+    /// Set by the player to skip the rest of the time between waves, for bonus gold.
+    pub call_next_wave_early: bool,
 }
 
 pub fn ideal_time_per_frame() -> Timer {
     Timer::from_seconds(0.1, TimerMode::Repeating)
 }
 
-pub fn ideal_animation_values() -> EnemyAnimation {
-    // this is the ideal values of the enemy sprite sheet that all enemy should have
-    let standard_enemy_animation = EnemyAnimation {
-        walk_up: AnimateSprite {
-            first: 0,
-            last: 3,
-            ..default()
-        },
-        walk_left: AnimateSprite {
-            first: 8,
-            last: 11,
-            ..default()
-        },
-        walk_down: AnimateSprite {
-            first: 12,
-            last: 15,
-            ..default()
-        },
-        ..default()
-    };
-    standard_enemy_animation
+impl Default for WaveControl {
+    fn default() -> Self {
+        Self {
+            enemy_types: Vec::new(),
+            waves: Vec::new(),
+            endless_wave: None,
+            wave_count: 0,
+            time_between_spawns: Timer::default(),
+            group_index: 0,
+            spawned_in_group: 0,
+            time_between_waves: Timer::from_seconds(TIME_BETWEEN_WAVES, TimerMode::Once),
+            first_wave_spawned: false,
+            call_next_wave_early: false,
+        }
+    }
 }
 
-pub fn load_enemy_sprites(
-    asset_server: Res<AssetServer>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    mut commands: Commands,
-) {
-    let mut textures: Vec<(Handle<Image>, Handle<TextureAtlasLayout>)> = Vec::new();
-    let mut animations: Vec<EnemyAnimation> = Vec::new();
+impl WaveControl {
+    /// Whether the time between waves is running, so the next wave can be called early
+    pub fn can_call_next_wave(&self) -> bool {
+        !self.time_between_waves.paused() && !self.time_between_waves.finished()
+    }
+
+    /// Gold earned by calling the next wave right now
+    pub fn early_call_bonus(&self) -> u16 {
+        (self.time_between_waves.remaining_secs() * EARLY_CALL_GOLD_PER_SEC).floor() as u16
+    }
+
+    pub fn enemy_type(&self, id: &str) -> Option<&EnemyType> {
+        self.enemy_types
+            .iter()
+            .find(|enemy_type| enemy_type.id == id)
+    }
+
+    pub fn current_wave(&self) -> Option<&WaveDefinition> {
+        self.waves
+            .get(self.wave_count as usize)
+            .or(self.endless_wave.as_ref())
+    }
 
-    let enemy_list = get_enemy_list();
+    /// Wave that spawns when the time to build ends, the current one until the first wave starts
+    pub fn next_wave_count(&self) -> u8 {
+        if self.first_wave_spawned {
+            self.wave_count.saturating_add(1)
+        } else {
+            self.wave_count
+        }
+    }
+
+    /// Definition of the next wave, generated like `start_wave` does after the script in endless
+    /// mode. `None` once the campaign has no waves left.
+    pub fn next_wave(&self, game_mode: GameMode) -> Option<WaveDefinition> {
+        let wave_count = self.next_wave_count();
+        match self.waves.get(wave_count as usize) {
+            Some(wave) => Some(wave.clone()),
+            None if game_mode == GameMode::Endless => {
+                Some(endless_wave(wave_count, &self.enemy_types))
+            }
+            None => None,
+        }
+    }
+
+    /// Whether the current wave is the last one of the script
+    pub fn is_last_scripted_wave(&self) -> bool {
+        self.wave_count as usize + 1 >= self.waves.len()
+    }
+
+    pub fn current_group(&self) -> Option<&SpawnGroup> {
+        self.current_wave()?.groups.get(self.group_index)
+    }
 
-    for (path, tile_size, columns, row, animation) in enemy_list {
-        let texture = asset_server.load(path);
-        let texture_atlas = TextureAtlasLayout::from_grid(tile_size, columns, row, None, None);
-        let atlas_handle = texture_atlas_layouts.add(texture_atlas);
+    pub fn wave_fully_spawned(&self) -> bool {
+        self.current_wave()
+            .is_some_and(|wave| self.group_index >= wave.groups.len())
+    }
 
-        textures.push((texture, atlas_handle));
-        animations.push(animation);
+    /// Starts spawning the first group of the current wave, in endless mode the waves after the
+    /// script are generated here
+    pub fn start_wave(&mut self, game_mode: GameMode) {
+        self.endless_wave = match game_mode {
+            GameMode::Endless if self.wave_count as usize >= self.waves.len() => {
+                Some(endless_wave(self.wave_count, &self.enemy_types))
+            }
+            _ => None,
+        };
+        self.group_index = 0;
+        self.start_group();
     }
 
-    commands.insert_resource(WaveControl {
-        textures,
-        animations,
-        wave_count: 0,
-        time_between_spawns: Timer::from_seconds(TIME_BETWEEN_SPAWNS, TimerMode::Repeating),
-        spawned_count_in_wave: 0,
-        time_between_waves: Timer::from_seconds(TIME_BETWEEN_WAVES, TimerMode::Once),
-        first_wave_spawned: false,
-    });
+    /// Moves to the next group of the current wave, once the current one is fully spawned
+    pub fn start_next_group(&mut self) {
+        self.group_index += 1;
+        self.start_group();
+    }
+
+    fn start_group(&mut self) {
+        self.spawned_in_group = 0;
+        let delay = self.current_group().map_or(0.0, |group| group.delay);
+        self.time_between_spawns = Timer::from_seconds(delay, TimerMode::Once);
+    }
+}
+
+/// Campaign ends with a victory after the last scripted wave, endless keeps generating waves
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Campaign,
+    Endless,
+}
+
+impl GameMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameMode::Campaign => "Campaign",
+            GameMode::Endless => "Endless",
+        }
+    }
+}
+
+// the game mode is picked with the M key in the start screen, or before playing again
+pub fn select_game_mode(input: Res<ButtonInput<KeyCode>>, mut game_mode: ResMut<GameMode>) {
+    if input.just_pressed(KeyCode::KeyM) {
+        *game_mode = match *game_mode {
+            GameMode::Campaign => GameMode::Endless,
+            GameMode::Endless => GameMode::Campaign,
+        };
+    }
+}
+
+// the next wave can be called early with the Enter key, or the button of the tower selected panel
+pub fn call_next_wave_early(
+    input: Res<ButtonInput<KeyCode>>,
+    mut wave_control: ResMut<WaveControl>,
+) {
+    if input.just_pressed(KeyCode::Enter) && wave_control.can_call_next_wave() {
+        wave_control.call_next_wave_early = true;
+    }
 }
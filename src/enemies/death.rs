@@ -0,0 +1,116 @@
+//! Killed enemies don't disappear at once: they play their death animation, or fade out when the
+//! sprite sheet has none, and are despawned after it. While dying they can't be targeted, don't
+//! move and don't count as alive for the wave. Enemies also flash when a shot hits them.
+
+use bevy::prelude::*;
+
+use super::*;
+
+/// Seconds a killed enemy without death frames takes to fade out
+pub const DEATH_FADE_SECS: f32 = 0.4;
+/// Size the fading enemy grows to, relative to its own
+pub const DEATH_FADE_SCALE: f32 = 1.4;
+pub const HIT_FLASH_SECS: f32 = 0.1;
+pub const HIT_FLASH_COLOR: Color = Color::srgb(1.0, 0.45, 0.45);
+
+#[derive(Component, Debug)]
+pub struct Dying {
+    pub timer: Timer,
+    /// Whether the death frames are played, otherwise the enemy fades out
+    pub animated: bool,
+    /// Scale of the enemy when it was killed
+    pub scale: Vec3,
+}
+
+#[derive(Component, Debug)]
+pub struct HitFlash {
+    pub timer: Timer,
+}
+
+impl Default for HitFlash {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(HIT_FLASH_SECS, TimerMode::Once),
+        }
+    }
+}
+
+/// Starts the death of an enemy, it lasts as long as its death frames
+pub fn start_dying(
+    commands: &mut Commands,
+    entity: Entity,
+    animation: &EnemyAnimation,
+    transform: &Transform,
+) {
+    let dying = match &animation.death {
+        Some(death) => Dying {
+            timer: Timer::new(
+                death.timer.duration() * (death.last.saturating_sub(death.first) + 1) as u32,
+                TimerMode::Once,
+            ),
+            animated: true,
+            scale: transform.scale,
+        },
+        None => Dying {
+            timer: Timer::from_seconds(DEATH_FADE_SECS, TimerMode::Once),
+            animated: false,
+            scale: transform.scale,
+        },
+    };
+    commands
+        .entity(entity)
+        .insert(dying)
+        .remove::<(HitFlash, LeadEnemy)>();
+}
+
+pub fn update_dying_enemies(
+    mut commands: Commands,
+    mut enemies: Query<(
+        Entity,
+        &mut Dying,
+        &mut Sprite,
+        &mut Transform,
+        &mut EnemyAnimation,
+    )>,
+    time: Res<Time>,
+) {
+    for (entity, mut dying, mut sprite, mut transform, mut animation) in &mut enemies {
+        dying.timer.tick(time.delta());
+        if dying.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if dying.animated {
+            // start the death frames from the first one
+            if animation.state != EnemyAnimationState::Death {
+                animation.state = EnemyAnimationState::Death;
+                sprite.color = Color::WHITE;
+                if let (Some(death), Some(atlas)) =
+                    (&mut animation.death, &mut sprite.texture_atlas)
+                {
+                    atlas.index = death.first;
+                    death.timer.reset();
+                }
+            }
+            continue;
+        }
+        // fade out while growing a bit, like a burst
+        let progress = dying.timer.fraction();
+        sprite.color = Color::WHITE.with_alpha(1.0 - progress);
+        transform.scale = dying.scale * (1.0 + (DEATH_FADE_SCALE - 1.0) * progress);
+    }
+}
+
+pub fn update_hit_flashes(
+    mut commands: Commands,
+    mut flashes: Query<(Entity, &mut HitFlash)>,
+    time: Res<Time>,
+) {
+    for (entity, mut flash) in &mut flashes {
+        flash.timer.tick(time.delta());
+        if flash.timer.finished() {
+            commands.entity(entity).remove::<HitFlash>();
+        }
+    }
+}
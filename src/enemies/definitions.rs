@@ -0,0 +1,201 @@
+//! Enemies are defined in the `assets/enemies/list.enemies.ron` file, so new enemies can be added
+//! without touching any Rust code. The file is hot reloaded while the game runs: every time it
+//! changes, the enemy types used by the `WaveControl` are rebuilt from it.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::*;
+
+pub const ENEMY_DEFINITIONS_PATH: &str = "enemies/list.enemies.ron";
+pub const ENEMY_DEFINITIONS_EXTENSIONS: &[&str] = &["enemies.ron"];
+
+/// How an enemy travels from its spawn to the goal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum Movement {
+    /// Follows the path
+    #[default]
+    Ground,
+    /// Flies in a straight line to the goal, only some towers can hit it
+    Flying,
+}
+
+/// Stats and sprite sheet layout of a single enemy.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemyDefinition {
+    /// Unique name used to reference the enemy
+    pub id: String,
+    /// Sprite sheet path, relative to the assets folder
+    pub sprite: String,
+    /// Width and height of a single frame
+    pub frame_size: (u32, u32),
+    pub columns: u32,
+    pub rows: u32,
+    /// First and last frames of every walking direction
+    pub walk_up: (usize, usize),
+    pub walk_down: (usize, usize),
+    pub walk_left: (usize, usize),
+    /// Optional directions, the left frames are mirrored when there are no right frames, and
+    /// diagonals without frames use the horizontal ones
+    #[serde(default)]
+    pub walk_right: Option<(usize, usize)>,
+    #[serde(default)]
+    pub walk_up_left: Option<(usize, usize)>,
+    #[serde(default)]
+    pub walk_up_right: Option<(usize, usize)>,
+    #[serde(default)]
+    pub walk_down_left: Option<(usize, usize)>,
+    #[serde(default)]
+    pub walk_down_right: Option<(usize, usize)>,
+    /// Optional frames played when the enemy is hit and when it dies
+    #[serde(default)]
+    pub hit: Option<(usize, usize)>,
+    #[serde(default)]
+    pub death: Option<(usize, usize)>,
+    /// Whether the sprite must be flipped, because it looks to the right when walking left
+    #[serde(default)]
+    pub flip: bool,
+    /// Life in the first wave, it scales with every wave
+    pub base_life: u16,
+    /// Speed in the first wave, it scales with every wave
+    pub base_speed: f32,
+    /// Gold given when the enemy is killed in the first wave, the bounty rules scale it
+    #[serde(default = "default_bounty")]
+    pub bounty: u16,
+    /// Lifes lost when the enemy reaches a goal
+    #[serde(default = "default_damage_to_base")]
+    pub damage_to_base: u8,
+    /// Multiplies the size of the sprite, useful to make bosses stand out
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    #[serde(default)]
+    pub movement: Movement,
+    #[serde(default)]
+    pub abilities: Vec<EnemyAbility>,
+    #[serde(default)]
+    pub boss: Option<BossDefinition>,
+    /// Status effects the shots can't apply to this enemy
+    #[serde(default)]
+    pub immunities: Vec<StatusEffectKind>,
+}
+
+fn default_bounty() -> u16 {
+    2
+}
+
+fn default_damage_to_base() -> u8 {
+    1
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+impl EnemyDefinition {
+    pub fn animation(&self) -> EnemyAnimation {
+        let animate_sprite = |(first, last): (usize, usize)| AnimateSprite {
+            first,
+            last,
+            ..default()
+        };
+        EnemyAnimation {
+            walk_up: animate_sprite(self.walk_up),
+            walk_down: animate_sprite(self.walk_down),
+            walk_left: animate_sprite(self.walk_left),
+            walk_right: self.walk_right.map(animate_sprite),
+            walk_up_left: self.walk_up_left.map(animate_sprite),
+            walk_up_right: self.walk_up_right.map(animate_sprite),
+            walk_down_left: self.walk_down_left.map(animate_sprite),
+            walk_down_right: self.walk_down_right.map(animate_sprite),
+            hit: self.hit.map(animate_sprite),
+            death: self.death.map(animate_sprite),
+            need_flip: self.flip,
+            ..default()
+        }
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct EnemyDefinitions {
+    pub enemies: Vec<EnemyDefinition>,
+}
+
+/// An enemy definition with its sprite sheet already loaded
+#[derive(Debug, Clone)]
+pub struct EnemyType {
+    pub id: String,
+    pub texture: Handle<Image>,
+    pub atlas_layout: Handle<TextureAtlasLayout>,
+    pub animation: EnemyAnimation,
+    pub base_life: u16,
+    pub base_speed: f32,
+    pub bounty: u16,
+    pub damage_to_base: u8,
+    pub scale: f32,
+    pub movement: Movement,
+    pub abilities: Vec<EnemyAbility>,
+    pub boss: Option<BossDefinition>,
+    pub immunities: Vec<StatusEffectKind>,
+}
+
+#[derive(Resource, Debug, Deref)]
+pub struct EnemyDefinitionsHandle(pub Handle<EnemyDefinitions>);
+
+pub fn load_enemy_definitions(asset_server: Res<AssetServer>, mut commands: Commands) {
+    let handle = asset_server.load(ENEMY_DEFINITIONS_PATH);
+    commands.insert_resource(EnemyDefinitionsHandle(handle));
+}
+
+/// Rebuilds the enemy types of the `WaveControl` when the definitions are loaded or changed
+pub fn apply_enemy_definitions(
+    mut events: EventReader<AssetEvent<EnemyDefinitions>>,
+    definitions_handle: Res<EnemyDefinitionsHandle>,
+    definitions: Res<Assets<EnemyDefinitions>>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut wave_control: ResMut<WaveControl>,
+) {
+    let changed = events.read().any(|event| {
+        event.is_added(definitions_handle.id()) || event.is_modified(definitions_handle.id())
+    });
+    if !changed {
+        return;
+    }
+    let Some(enemy_definitions) = definitions.get(definitions_handle.id()) else {
+        return;
+    };
+
+    wave_control.enemy_types = enemy_definitions
+        .enemies
+        .iter()
+        .map(|definition| {
+            let (width, height) = definition.frame_size;
+            let texture_atlas = TextureAtlasLayout::from_grid(
+                UVec2::new(width, height),
+                definition.columns,
+                definition.rows,
+                None,
+                None,
+            );
+            EnemyType {
+                id: definition.id.clone(),
+                texture: asset_server.load(&definition.sprite),
+                atlas_layout: texture_atlas_layouts.add(texture_atlas),
+                animation: definition.animation(),
+                base_life: definition.base_life,
+                base_speed: definition.base_speed,
+                bounty: definition.bounty,
+                damage_to_base: definition.damage_to_base,
+                scale: definition.scale,
+                movement: definition.movement,
+                abilities: definition.abilities.clone(),
+                boss: definition.boss.clone(),
+                immunities: definition.immunities.clone(),
+            }
+        })
+        .collect();
+    info!(
+        "enemy definitions loaded: {} enemies",
+        wave_control.enemy_types.len()
+    );
+}
@@ -12,152 +12,220 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use bevy::prelude::*;
+use rand::Rng;
 
 use crate::{
-    solana::{update_player_values, PlayerInfo, SolClient, Tasks, Wallet},
-    tower_building::{GameState, Lifes},
+    solana::{update_player_values, PlayerInfo, SolClient, Tasks, TxAction, TxAmount, Wallet},
+    tower_building::{Difficulty, GameRng, GameState, Gold, Lifes, RunStats},
 };
 
 use super::{
-    EnemyAnimation, EnemyAnimationState, WaveControl, INITIAL_ENEMY_LIFE, MAX_ENEMIES_PER_WAVE,
-    SCALAR, SCALE, SPAWN_X_LOCATION, SPAWN_Y_LOCATION,
+    insert_abilities, Boss, DistanceToGoal, Dying, EnemyAnimation, EnemyAnimationState, EnemyPaths,
+    EnemyType, GameMode, Movement, PathFollower, StatusEffects, WaveControl, SCALE,
 };
 
 #[derive(Component)]
 pub struct Enemy {
+    /// `id` of the enemy type in the enemy definitions
+    pub id: String,
     pub life: u16,
+    /// Life the enemy spawned with, regeneration and healing never go over it
+    pub max_life: u16,
     pub speed: f32,
+    /// Base gold given when killed, see `BountyRules`
+    pub bounty: u16,
+    /// Lifes lost when it reaches a goal
+    pub damage_to_base: u8,
 }
 
-#[derive(Debug, Component, Deref, DerefMut, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BreakPointLvl(pub u8);
+/// Marks the enemies that fly straight to the goal instead of following the whole path
+#[derive(Component, Debug)]
+pub struct Flying;
 
-pub fn spawn_wave(mut commands: Commands, time: Res<Time>, mut wave_control: ResMut<WaveControl>) {
-    if wave_control.wave_count == wave_control.textures.len() as u8 {
+/// Flying enemies are drawn over the ground enemies and the shots
+pub const FLYING_Z: f32 = 2.0;
+
+/// Spawns the groups of the current wave from the wave script, one after the other.
+pub fn spawn_wave(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut wave_control: ResMut<WaveControl>,
+    difficulty: Res<Difficulty>,
+    enemy_paths: Res<EnemyPaths>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    // the wave is fully spawned, or the last wave was already played
+    let Some(group) = wave_control.current_group().cloned() else {
+        return;
+    };
+
+    if wave_control.spawned_in_group >= group.count {
+        wave_control.start_next_group();
         return;
     }
 
+    // wait for the map to be loaded
+    let Some((spawn_position, mut path_follower)) = enemy_paths.spawn(group.spawn.as_deref())
+    else {
+        return;
+    };
+
     wave_control.time_between_spawns.tick(time.delta());
+    if !wave_control.time_between_spawns.just_finished() {
+        return;
+    }
 
-    if wave_control.spawned_count_in_wave < MAX_ENEMIES_PER_WAVE
-        && wave_control.time_between_spawns.just_finished()
-    {
-        let wave_image = &wave_control.textures[wave_control.wave_count as usize];
-        let enemy_animation = &wave_control.animations[wave_control.wave_count as usize];
-        let enemy_life = (INITIAL_ENEMY_LIFE as f32
-            * (1.2 + SCALAR).powf(wave_control.wave_count as f32))
-        .round() as u16;
-        let enemy_speed = (75.0 * (1.05f32).powf(wave_control.wave_count as f32)).min(300.0);
-        info!("enemy life: {}, enemy speed: {:?}", enemy_life, enemy_speed);
-
-        commands.spawn((
-            Sprite::from_atlas_image(
-                wave_image.0.clone(),
-                TextureAtlas {
-                    layout: wave_image.1.clone(),
-                    index: enemy_animation.walk_left.first,
-                },
-            ),
-            Transform {
-                translation: Vec3::new(SPAWN_X_LOCATION, SPAWN_Y_LOCATION, 1.0),
-                scale: Vec3::splat(SCALE),
-                ..default()
-            },
-            Enemy {
-                life: enemy_life,
-                speed: enemy_speed,
+    let Some(enemy_type) = wave_control.enemy_type(&group.enemy) else {
+        warn!("unknown enemy in the wave script: {}", group.enemy);
+        wave_control.start_next_group();
+        return;
+    };
+
+    // drawn in spawn order, so the forks of a seeded run are the same every time
+    path_follower.fork_seed = game_rng.random();
+    spawn_enemy(
+        &mut commands,
+        enemy_type,
+        wave_control.wave_count,
+        group.health_multiplier,
+        spawn_position.extend(1.0),
+        path_follower,
+        &difficulty,
+    );
+
+    wave_control.spawned_in_group += 1;
+    if wave_control.spawned_in_group < group.count {
+        wave_control.time_between_spawns = Timer::from_seconds(group.interval, TimerMode::Once);
+    } else {
+        wave_control.start_next_group();
+    }
+}
+
+/// Spawns an enemy with the life and speed it has in the given wave, plus its abilities.
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    enemy_type: &EnemyType,
+    wave_count: u8,
+    health_multiplier: f32,
+    translation: Vec3,
+    path_follower: PathFollower,
+    difficulty: &Difficulty,
+) -> Entity {
+    let life_scale = difficulty.life_scale(wave_count) * health_multiplier;
+    let enemy_life = (enemy_type.base_life as f32 * life_scale).round() as u16;
+    let enemy_speed = difficulty.enemy_speed(enemy_type.base_speed, wave_count);
+    info!(
+        "enemy: {}, life: {}, speed: {:?}",
+        enemy_type.id, enemy_life, enemy_speed
+    );
+
+    let translation = match enemy_type.movement {
+        Movement::Ground => translation,
+        Movement::Flying => translation.with_z(FLYING_Z),
+    };
+
+    let mut enemy = commands.spawn((
+        Sprite::from_atlas_image(
+            enemy_type.texture.clone(),
+            TextureAtlas {
+                layout: enemy_type.atlas_layout.clone(),
+                index: enemy_type.animation.walk_left.first,
             },
-            enemy_animation.clone(),
-            BreakPointLvl(0),
-        ));
-        wave_control.spawned_count_in_wave += 1;
+        ),
+        Transform {
+            translation,
+            scale: Vec3::splat(SCALE * enemy_type.scale),
+            ..default()
+        },
+        Enemy {
+            id: enemy_type.id.clone(),
+            life: enemy_life,
+            max_life: enemy_life,
+            speed: enemy_speed,
+            bounty: enemy_type.bounty,
+            damage_to_base: enemy_type.damage_to_base,
+        },
+        enemy_type.animation.clone(),
+        path_follower,
+        DistanceToGoal::default(),
+        StatusEffects::new(enemy_type.immunities.clone()),
+    ));
+    if enemy_type.movement == Movement::Flying {
+        enemy.insert(Flying);
+    }
+    insert_abilities(&mut enemy, &enemy_type.abilities, life_scale);
+    if let Some(boss) = &enemy_type.boss {
+        enemy.insert(Boss::new(boss.clone()));
     }
+    enemy.id()
 }
 
-/// Defines a set of predefined points in the game world where enemies change direction.
-/// These points dictate the movement path of the enemies.
-pub const BREAK_POINTS: [Vec2; 6] = [
-    Vec2::new(260.0, SPAWN_Y_LOCATION),
-    Vec2::new(260.0, -205.0),
-    Vec2::new(-230.0, -205.0),
-    Vec2::new(-230.0, SPAWN_Y_LOCATION),
-    Vec2::new(-455.0, SPAWN_Y_LOCATION),
-    Vec2::new(-455.0, -375.0),
-];
-
-/// Moves enemies along a predefined path based on their current position and speed.
-/// The movement is determined by comparing the enemy’s position to predefined breakpoints.
-/// Once an enemy reaches a specific breakpoint, it updates its direction accordingly.
+/// Moves the enemies along the path, flying enemies go straight to the goal.
+/// The walk animation follows the direction they are walking in.
 pub fn move_enemies(
-    mut enemies: Query<(
-        &mut Transform,
-        &Enemy,
-        &mut BreakPointLvl,
-        &mut EnemyAnimation,
-    )>,
+    mut enemies: Query<
+        (
+            &mut Transform,
+            &Enemy,
+            &mut PathFollower,
+            &mut DistanceToGoal,
+            &mut EnemyAnimation,
+            &StatusEffects,
+            Has<Flying>,
+        ),
+        Without<Dying>,
+    >,
+    mut enemy_paths: ResMut<EnemyPaths>,
     time: Res<Time>,
 ) {
-    for (mut enemy_transform, enemy, mut breal_point_lvl, mut enemy_animation) in &mut enemies {
-        let translation = enemy_transform.translation;
-        let speed = enemy.speed * time.delta_secs();
-
-        // 1. -x
-        if translation.x > BREAK_POINTS[0].x {
-            enemy_transform.translation.x -= speed;
-            if enemy_animation.need_flip {
-                enemy_transform.scale.x = -SCALE;
-            }
-        }
-        // 2. -y
-        else if translation.x <= BREAK_POINTS[0].x
-            && translation.x > BREAK_POINTS[2].x
-            && translation.y > BREAK_POINTS[1].y
-        {
-            enemy_transform.translation.y -= speed;
-            enemy_animation.state = EnemyAnimationState::WalkDown;
-            *breal_point_lvl = BreakPointLvl(1);
-        }
-        // 3. -x
-        else if translation.y <= BREAK_POINTS[1].y && translation.x >= BREAK_POINTS[2].x {
-            enemy_transform.translation.x -= speed;
-            enemy_animation.state = EnemyAnimationState::WalkLeft;
-            *breal_point_lvl = BreakPointLvl(2);
-        }
-        // 4. +y
-        else if translation.y < SPAWN_Y_LOCATION
-            && translation.x <= BREAK_POINTS[2].x
-            && translation.x > BREAK_POINTS[4].x
-        {
-            enemy_animation.state = EnemyAnimationState::WalkUp;
-            enemy_transform.translation.y += speed;
-            *breal_point_lvl = BreakPointLvl(3);
-        }
-        // 5. -x
-        else if translation.y >= SPAWN_Y_LOCATION && translation.x >= BREAK_POINTS[4].x {
-            enemy_transform.translation.x -= speed;
-            enemy_animation.state = EnemyAnimationState::WalkLeft;
-            *breal_point_lvl = BreakPointLvl(4);
+    for (
+        mut enemy_transform,
+        enemy,
+        mut path_follower,
+        mut distance_to_goal,
+        mut enemy_animation,
+        status_effects,
+        flying,
+    ) in &mut enemies
+    {
+        if flying {
+            enemy_paths.skip_to_goal(&mut path_follower);
         }
-        // 6. -y
-        else if translation.y > BREAK_POINTS[5].y && translation.x <= BREAK_POINTS[4].x {
-            enemy_transform.translation.y -= speed;
-            enemy_animation.state = EnemyAnimationState::WalkDown;
-            *breal_point_lvl = BreakPointLvl(5);
+
+        let mut position = enemy_transform.translation.truncate();
+        let direction = enemy_paths.advance(
+            &mut path_follower,
+            &mut position,
+            enemy.speed * status_effects.speed_multiplier() * time.delta_secs(),
+        );
+        enemy_transform.translation.x = position.x;
+        enemy_transform.translation.y = position.y;
+        distance_to_goal.0 = enemy_paths.distance_to_goal(&path_follower, position);
+
+        if direction == Vec2::ZERO {
+            continue;
         }
+        enemy_animation.state = EnemyAnimationState::from_direction(direction);
     }
 }
 
+/// Enemies reaching a goal leak through, each one costs its damage to the base in lifes
 pub fn game_over(
     mut commands: Commands,
-    mut enemies: Query<(&Transform, Entity), With<Enemy>>,
+    mut enemies: Query<(&PathFollower, Entity, &Enemy), Without<Dying>>,
     mut lifes: ResMut<Lifes>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut stats: ResMut<RunStats>,
+    enemy_paths: Res<EnemyPaths>,
+    wave_control: Res<WaveControl>,
 ) {
-    for (enemy_transform, entity) in &mut enemies {
-        let translation = enemy_transform.translation;
-        if translation.y <= BREAK_POINTS[5].y {
+    for (path_follower, entity, enemy) in &mut enemies {
+        if enemy_paths.reached_goal(path_follower) {
             commands.entity(entity).despawn();
-            lifes.0 = lifes.0.saturating_sub(1);
+            let damage = enemy.damage_to_base;
+            stats.lives_lost += damage.min(lifes.0) as u16;
+            stats.record_leak(wave_control.wave_count);
+            lifes.0 = lifes.0.saturating_sub(damage);
         }
     }
     if lifes.0 == 0 {
@@ -165,13 +233,16 @@ pub fn game_over(
     }
 }
 
-pub fn reset_wave_control_on_game_over(mut wave_control: ResMut<WaveControl>) {
+pub fn reset_wave_control_on_game_over(
+    mut wave_control: ResMut<WaveControl>,
+    game_mode: Res<GameMode>,
+) {
     wave_control.wave_count = 0;
-    wave_control.spawned_count_in_wave = 0;
+    wave_control.start_wave(*game_mode);
     wave_control.time_between_waves.unpause();
     wave_control.time_between_waves.reset();
-    wave_control.time_between_spawns.reset();
     wave_control.first_wave_spawned = false;
+    wave_control.call_next_wave_early = false;
 }
 
 pub fn despawn_all_enemies_in_game_over(
@@ -183,11 +254,32 @@ pub fn despawn_all_enemies_in_game_over(
     }
 }
 
+/// Memo sent with the on-chain score, so scores reached with different settings can be compared
+pub fn score_memo(
+    wave_count: u8,
+    game_mode: GameMode,
+    difficulty: &Difficulty,
+    seed: u64,
+) -> String {
+    format!(
+        "wave={} mode={} difficulty={} seed={}",
+        wave_count,
+        game_mode.label(),
+        difficulty.score_label(),
+        seed
+    )
+}
+
 pub fn wave_control(
     time: Res<Time>,
     mut wave_control: ResMut<WaveControl>,
-    enemies: Query<Entity, With<Enemy>>,
+    enemies: Query<Entity, (With<Enemy>, Without<Dying>)>,
     mut game_state: ResMut<NextState<GameState>>,
+    game_mode: Res<GameMode>,
+    mut stats: ResMut<RunStats>,
+    difficulty: Res<Difficulty>,
+    game_rng: Res<GameRng>,
+    mut gold: ResMut<Gold>,
     solana_resources: (
         ResMut<Tasks>,
         ResMut<Wallet>,
@@ -198,42 +290,75 @@ pub fn wave_control(
     // tick cooldown timer
     wave_control.time_between_waves.tick(time.delta());
 
+    // skip the rest of the cooldown, the skipped time is paid in gold
+    if wave_control.call_next_wave_early {
+        wave_control.call_next_wave_early = false;
+        if wave_control.can_call_next_wave() {
+            let bonus = wave_control.early_call_bonus();
+            gold.0 = gold.0.saturating_add(bonus);
+            stats.gold_earned += bonus as u32;
+            info!("next wave called early, bonus gold: {}", bonus);
+            let remaining = wave_control.time_between_waves.remaining();
+            wave_control.time_between_waves.tick(remaining);
+        }
+    }
+
     if !wave_control.first_wave_spawned {
         // start first wave after timer ends
         if wave_control.time_between_waves.just_finished() {
             game_state.set(GameState::Attacking);
             wave_control.time_between_waves.pause();
             wave_control.time_between_waves.reset();
+            wave_control.start_wave(*game_mode);
             info!("first wave started");
             wave_control.first_wave_spawned = true;
         }
     }
 
     let all_enemies_killed = enemies.iter().next().is_none();
-    let wave_fully_spawned = wave_control.spawned_count_in_wave == MAX_ENEMIES_PER_WAVE;
+    let wave_fully_spawned = wave_control.wave_fully_spawned();
 
     if wave_fully_spawned && all_enemies_killed {
+        // the campaign is won once the last scripted wave is cleared
+        if *game_mode == GameMode::Campaign && wave_control.is_last_scripted_wave() {
+            stats.waves_cleared = wave_control.wave_count.saturating_add(1);
+            info!("last wave cleared, victory!");
+            game_state.set(GameState::Victory);
+            return;
+        }
+
         // control cooldown between waves
         if wave_control.time_between_waves.paused() {
+            stats.waves_cleared = wave_control.wave_count.saturating_add(1);
             wave_control.time_between_waves.unpause();
             wave_control.time_between_waves.reset();
             game_state.set(GameState::Building);
         }
 
         if wave_control.time_between_waves.just_finished() {
-            wave_control.spawned_count_in_wave = 0;
-            wave_control.wave_count += 1;
+            wave_control.wave_count = wave_control.wave_count.saturating_add(1);
+            wave_control.start_wave(*game_mode);
             let (mut tasks, signer, client, player_info) = solana_resources;
             let now = SystemTime::now();
-            let last_time_played = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let last_time_played = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
             info!("last_time_played: {}", last_time_played);
-            tasks.add_task(update_player_values(
-                signer.keypair.clone(),
-                client.clone(),
-                wave_control.wave_count,
-                last_time_played,
-                player_info.address,
-            ));
+            tasks.add_tx_task(
+                TxAction::UpdatePlayer,
+                TxAmount::Nothing,
+                update_player_values(
+                    signer.keypair.clone(),
+                    client.clone(),
+                    wave_control.wave_count,
+                    last_time_played,
+                    player_info.address,
+                    score_memo(
+                        wave_control.wave_count,
+                        *game_mode,
+                        &difficulty,
+                        game_rng.seed,
+                    ),
+                ),
+            );
             wave_control.time_between_waves.pause();
             wave_control.time_between_waves.reset();
             game_state.set(GameState::Attacking);
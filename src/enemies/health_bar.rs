@@ -0,0 +1,133 @@
+//! Health bars drawn over the enemies. They are separate entities following their enemy, so the
+//! enemy scale and flip don't affect them, and they are hidden while the enemy has full life.
+//! The lead enemy, the closest one to a goal, gets a marker over its health bar.
+
+use bevy::prelude::*;
+
+use super::*;
+
+pub const HEALTH_BAR_WIDTH: f32 = 40.0;
+pub const HEALTH_BAR_HEIGHT: f32 = 5.0;
+/// Distance over the center of an enemy with the default scale
+pub const HEALTH_BAR_OFFSET: f32 = 40.0;
+pub const HEALTH_BAR_Z: f32 = 3.0;
+pub const HEALTH_BAR_BACKGROUND_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.8);
+pub const HEALTH_BAR_FILL_COLOR: Color = Color::srgb(0.85, 0.15, 0.15);
+pub const LEAD_ENEMY_MARKER_SIZE: f32 = 8.0;
+pub const LEAD_ENEMY_MARKER_COLOR: Color = Color::srgb(1.0, 0.8, 0.1);
+
+#[derive(Component, Debug)]
+pub struct HealthBar {
+    pub enemy: Entity,
+}
+
+#[derive(Component, Debug)]
+pub struct HealthBarFill;
+
+#[derive(Component, Debug)]
+pub struct LeadEnemyMarker;
+
+pub fn spawn_health_bars(mut commands: Commands, enemies: Query<Entity, Added<Enemy>>) {
+    for enemy in &enemies {
+        commands
+            .spawn((
+                Sprite {
+                    color: HEALTH_BAR_BACKGROUND_COLOR,
+                    custom_size: Some(Vec2::new(HEALTH_BAR_WIDTH, HEALTH_BAR_HEIGHT)),
+                    ..default()
+                },
+                Transform::from_xyz(0.0, 0.0, HEALTH_BAR_Z),
+                Visibility::Hidden,
+                HealthBar { enemy },
+            ))
+            .with_child((
+                Sprite {
+                    color: HEALTH_BAR_FILL_COLOR,
+                    custom_size: Some(Vec2::new(HEALTH_BAR_WIDTH, HEALTH_BAR_HEIGHT)),
+                    anchor: bevy::sprite::Anchor::CenterLeft,
+                    ..default()
+                },
+                Transform::from_xyz(-HEALTH_BAR_WIDTH / 2.0, 0.0, 0.1),
+                HealthBarFill,
+            ));
+    }
+}
+
+/// Moves every health bar over its enemy and resizes the fill with the remaining life,
+/// the bar is despawned with its enemy
+pub fn update_health_bars(
+    mut commands: Commands,
+    mut health_bars: Query<
+        (
+            Entity,
+            &HealthBar,
+            &mut Transform,
+            &mut Visibility,
+            &Children,
+        ),
+        Without<Enemy>,
+    >,
+    mut fills: Query<&mut Sprite, With<HealthBarFill>>,
+    enemies: Query<(&Transform, &Enemy, Has<Dying>), Without<HealthBar>>,
+) {
+    for (entity, health_bar, mut transform, mut visibility, children) in &mut health_bars {
+        let Ok((enemy_transform, enemy, dying)) = enemies.get(health_bar.enemy) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        let offset = HEALTH_BAR_OFFSET * enemy_transform.scale.y / SCALE;
+        transform.translation.x = enemy_transform.translation.x;
+        transform.translation.y = enemy_transform.translation.y + offset;
+
+        *visibility = if enemy.life >= enemy.max_life || dying {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+
+        let life_fraction = enemy.life as f32 / enemy.max_life.max(1) as f32;
+        for child in children.iter() {
+            if let Ok(mut fill) = fills.get_mut(*child) {
+                fill.custom_size = Some(Vec2::new(
+                    HEALTH_BAR_WIDTH * life_fraction,
+                    HEALTH_BAR_HEIGHT,
+                ));
+            }
+        }
+    }
+}
+
+pub fn spawn_lead_enemy_marker(mut commands: Commands) {
+    commands.spawn((
+        Sprite {
+            color: LEAD_ENEMY_MARKER_COLOR,
+            custom_size: Some(Vec2::splat(LEAD_ENEMY_MARKER_SIZE)),
+            ..default()
+        },
+        // a rotated square, drawn as a diamond
+        Transform::from_xyz(0.0, 0.0, HEALTH_BAR_Z)
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+        Visibility::Hidden,
+        LeadEnemyMarker,
+    ));
+}
+
+/// Moves the marker over the lead enemy, it's hidden while there are no enemies
+pub fn update_lead_enemy_marker(
+    mut markers: Query<(&mut Transform, &mut Visibility), With<LeadEnemyMarker>>,
+    lead_enemies: Query<&Transform, (With<LeadEnemy>, Without<LeadEnemyMarker>, Without<Dying>)>,
+) {
+    for (mut transform, mut visibility) in &mut markers {
+        let Some(enemy_transform) = lead_enemies.iter().next() else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        let offset =
+            HEALTH_BAR_OFFSET * enemy_transform.scale.y / SCALE + LEAD_ENEMY_MARKER_SIZE * 1.5;
+        transform.translation.x = enemy_transform.translation.x;
+        transform.translation.y = enemy_transform.translation.y + offset;
+        *visibility = Visibility::Inherited;
+    }
+}
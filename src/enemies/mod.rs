@@ -1,9 +1,25 @@
+pub mod abilities;
 pub mod animation;
+pub mod boss;
 pub mod config;
+pub mod death;
+pub mod definitions;
 pub mod ecs;
-pub mod enemy_list;
+pub mod health_bar;
+pub mod path;
+pub mod ron_asset;
+pub mod status_effects;
+pub mod waves;
 
-pub use enemy_list::*;
+pub use abilities::*;
 pub use animation::*;
+pub use boss::*;
 pub use config::*;
+pub use death::*;
+pub use definitions::*;
 pub use ecs::*;
+pub use health_bar::*;
+pub use path::*;
+pub use ron_asset::*;
+pub use status_effects::*;
+pub use waves::*;
@@ -0,0 +1,671 @@
+//! Enemies walk the map following ordered lists of waypoints, from a spawn to a goal.
+//! The spawns and paths are defined in the `assets/maps/default.map.ron` file, a path can fork
+//! into branches at its end, and it's hot reloaded like the enemy definitions.
+//!
+//! Each enemy keeps track of the path and waypoint it is walking to and the distance it has
+//! traveled, so the movement doesn't depend on the shape of the map.
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Deserialize;
+
+use super::Dying;
+
+pub const MAP_PATH: &str = "maps/default.map.ron";
+pub const MAP_EXTENSIONS: &[&str] = &["map.ron"];
+
+/// How enemies pick one of the branches at the end of a path
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub enum Fork {
+    #[default]
+    Random,
+    /// Branches are taken one after the other
+    Alternate,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathDefinition {
+    pub id: String,
+    pub waypoints: Vec<(f32, f32)>,
+    /// `id` of the paths the enemies can continue on, a path without branches ends in a goal
+    #[serde(default)]
+    pub branches: Vec<String>,
+    #[serde(default)]
+    pub fork: Fork,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnDefinition {
+    pub id: String,
+    /// `id` of the path walked from this spawn, enemies spawn at its first waypoint
+    pub path: String,
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct MapDefinition {
+    pub spawns: Vec<SpawnDefinition>,
+    pub paths: Vec<PathDefinition>,
+}
+
+#[derive(Resource, Debug, Deref)]
+pub struct MapDefinitionHandle(pub Handle<MapDefinition>);
+
+#[derive(Debug, Clone)]
+pub struct EnemyPath {
+    pub id: String,
+    pub waypoints: Vec<Vec2>,
+    /// Index of the branches in `EnemyPaths::paths`
+    pub branches: Vec<usize>,
+    pub fork: Fork,
+    /// Number of enemies that went through the fork, used to alternate the branches
+    pub forks_taken: usize,
+    /// Distance from every waypoint to the closest goal, going through the shortest branches
+    pub distance_to_goal: Vec<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnemySpawn {
+    pub id: String,
+    /// Index of the path in `EnemyPaths::paths`
+    pub path: usize,
+}
+
+/// Spawns and paths of the current map, built from the map asset
+#[derive(Resource, Debug, Default)]
+pub struct EnemyPaths {
+    pub paths: Vec<EnemyPath>,
+    pub spawns: Vec<EnemySpawn>,
+}
+
+/// Progress of an enemy along the paths of the map
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct PathFollower {
+    /// Index of the path in `EnemyPaths::paths`
+    pub path: usize,
+    /// Index of the waypoint the enemy walks to
+    pub waypoint: usize,
+    /// Distance walked since the spawn
+    pub traveled: f32,
+    /// Drawn from the `GameRng` when the enemy spawns, the random forks only depend on it so
+    /// they don't change with the order the enemies reach them
+    pub fork_seed: u64,
+}
+
+/// Distance an enemy still has to walk to reach a goal, updated when it moves. Towers target the
+/// enemy with the lowest one, it starts at `f32::MAX` until the enemy first moves.
+#[derive(Component, Debug, Clone, Copy, Deref, DerefMut, PartialEq, PartialOrd)]
+pub struct DistanceToGoal(pub f32);
+
+impl Default for DistanceToGoal {
+    fn default() -> Self {
+        Self(f32::MAX)
+    }
+}
+
+/// Marks the enemy closest to a goal
+#[derive(Component, Debug)]
+pub struct LeadEnemy;
+
+impl EnemyPaths {
+    /// Resolves the ids of the map definition, unknown paths are skipped with a warning
+    pub fn from_definition(definition: &MapDefinition) -> Self {
+        let index_of = |id: &str| {
+            let index = definition
+                .paths
+                .iter()
+                .position(|path| path.id == id && !path.waypoints.is_empty());
+            if index.is_none() {
+                warn!("unknown or empty path in the map: {}", id);
+            }
+            index
+        };
+
+        let mut paths: Vec<EnemyPath> = definition
+            .paths
+            .iter()
+            .map(|path| EnemyPath {
+                id: path.id.clone(),
+                waypoints: path
+                    .waypoints
+                    .iter()
+                    .map(|&(x, y)| Vec2::new(x, y))
+                    .collect(),
+                branches: path.branches.iter().filter_map(|id| index_of(id)).collect(),
+                fork: path.fork,
+                forks_taken: 0,
+                distance_to_goal: Vec::new(),
+            })
+            .collect();
+        compute_distances_to_goal(&mut paths);
+
+        let spawns = definition
+            .spawns
+            .iter()
+            .filter_map(|spawn| {
+                Some(EnemySpawn {
+                    id: spawn.id.clone(),
+                    path: index_of(&spawn.path)?,
+                })
+            })
+            .collect();
+
+        Self { paths, spawns }
+    }
+
+    /// Position and path of the spawn with the given `id`, the first spawn is used when there is
+    /// no `id` or it's unknown. `None` until the map is loaded.
+    pub fn spawn(&self, id: Option<&str>) -> Option<(Vec2, PathFollower)> {
+        let spawn = match id {
+            Some(id) => self.spawns.iter().find(|spawn| spawn.id == id).or_else(|| {
+                warn!("unknown spawn in the wave script: {}", id);
+                self.spawns.first()
+            }),
+            None => self.spawns.first(),
+        }?;
+        let start = *self.paths.get(spawn.path)?.waypoints.first()?;
+
+        Some((
+            start,
+            PathFollower {
+                path: spawn.path,
+                waypoint: 1,
+                traveled: 0.0,
+                fork_seed: 0,
+            },
+        ))
+    }
+
+    pub fn next_waypoint(&self, follower: &PathFollower) -> Option<Vec2> {
+        self.paths
+            .get(follower.path)?
+            .waypoints
+            .get(follower.waypoint)
+            .copied()
+    }
+
+    /// Whether the enemy walked the whole path and there are no branches to continue on
+    pub fn reached_goal(&self, follower: &PathFollower) -> bool {
+        self.paths.get(follower.path).is_none_or(|path| {
+            follower.waypoint >= path.waypoints.len() && path.branches.is_empty()
+        })
+    }
+
+    /// Distance left to the closest goal from `position`, for an enemy walking to its next waypoint
+    pub fn distance_to_goal(&self, follower: &PathFollower, position: Vec2) -> f32 {
+        let Some(path) = self.paths.get(follower.path) else {
+            return 0.0;
+        };
+        match path.waypoints.get(follower.waypoint) {
+            Some(waypoint) => {
+                position.distance(*waypoint) + path.distance_to_goal[follower.waypoint]
+            }
+            // at a fork, waiting to take a branch
+            None => shortest_branch(&self.paths, &path.branches, position).unwrap_or(0.0),
+        }
+    }
+
+    /// Points the enemy to the goal at the end of its path, following the first branch of every
+    /// fork. Used by the flying enemies, that go straight to it.
+    pub fn skip_to_goal(&self, follower: &mut PathFollower) {
+        let mut path = follower.path;
+        for _ in 0..self.paths.len() {
+            match self.paths.get(path).and_then(|p| p.branches.first()) {
+                Some(&branch) => path = branch,
+                None => break,
+            }
+        }
+        let Some(goal) = self.paths.get(path) else {
+            return;
+        };
+
+        let last_waypoint = goal.waypoints.len().saturating_sub(1);
+        if path != follower.path || follower.waypoint < last_waypoint {
+            follower.path = path;
+            follower.waypoint = last_waypoint;
+        }
+    }
+
+    /// Moves `position` up to `distance` along the paths, going through as many waypoints and
+    /// forks as needed so it never goes past them. Returns the direction of the last step.
+    pub fn advance(
+        &mut self,
+        follower: &mut PathFollower,
+        position: &mut Vec2,
+        mut distance: f32,
+    ) -> Vec2 {
+        let mut direction = Vec2::ZERO;
+        let mut forks = 0;
+        while distance > 0.0 {
+            let Some(waypoint) = self.next_waypoint(follower) else {
+                // a map whose branches loop back without any length would never use the distance
+                forks += 1;
+                if forks > self.paths.len() {
+                    if let Some(path) = self.paths.get(follower.path) {
+                        warn_once!(
+                            "path {} loops through its branches without any length",
+                            path.id
+                        );
+                    }
+                    break;
+                }
+                // end of the path, keep walking on one of its branches
+                match self.take_branch(follower) {
+                    Some(branch) => {
+                        follower.path = branch;
+                        follower.waypoint = 0;
+                        continue;
+                    }
+                    None => break,
+                }
+            };
+
+            let to_waypoint = waypoint - *position;
+            let remaining = to_waypoint.length();
+            if remaining > 0.0 {
+                direction = to_waypoint / remaining;
+            }
+
+            if remaining <= distance {
+                *position = waypoint;
+                follower.traveled += remaining;
+                follower.waypoint += 1;
+                distance -= remaining;
+            } else {
+                *position += direction * distance;
+                follower.traveled += distance;
+                distance = 0.0;
+            }
+        }
+        direction
+    }
+
+    /// Points a follower of the `old` paths to the same path in these ones, matched by id, so the
+    /// enemies keep walking when the map is reloaded. Followers of a removed path walk to the
+    /// closest waypoint instead.
+    pub fn remap(&self, old: &EnemyPaths, follower: &mut PathFollower, position: Vec2) {
+        let same_path = old.paths.get(follower.path).and_then(|old_path| {
+            self.paths
+                .iter()
+                .position(|path| path.id == old_path.id && !path.waypoints.is_empty())
+        });
+        match same_path {
+            Some(path) => {
+                follower.path = path;
+                // a waypoint past the end only means something if the path still forks
+                let waypoints = self.paths[path].waypoints.len();
+                let last = if self.paths[path].branches.is_empty() {
+                    waypoints - 1
+                } else {
+                    waypoints
+                };
+                follower.waypoint = follower.waypoint.min(last);
+            }
+            None => {
+                let closest = self
+                    .paths
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(path, p)| {
+                        p.waypoints
+                            .iter()
+                            .enumerate()
+                            .map(move |(waypoint, point)| {
+                                (path, waypoint, point.distance(position))
+                            })
+                    })
+                    .min_by(|a, b| a.2.total_cmp(&b.2));
+                if let Some((path, waypoint, _)) = closest {
+                    follower.path = path;
+                    follower.waypoint = waypoint;
+                }
+            }
+        }
+    }
+
+    fn take_branch(&mut self, follower: &mut PathFollower) -> Option<usize> {
+        let path = self.paths.get_mut(follower.path)?;
+        if path.branches.is_empty() {
+            return None;
+        }
+
+        let index = match path.fork {
+            Fork::Random => {
+                let mut rng = StdRng::seed_from_u64(follower.fork_seed);
+                follower.fork_seed = rng.random();
+                rng.random_range(0..path.branches.len())
+            }
+            Fork::Alternate => path.forks_taken % path.branches.len(),
+        };
+        path.forks_taken += 1;
+        Some(path.branches[index])
+    }
+}
+
+/// Distance from `position` to the closest goal through one of the `branches`
+fn shortest_branch(paths: &[EnemyPath], branches: &[usize], position: Vec2) -> Option<f32> {
+    branches
+        .iter()
+        .filter_map(|&branch| {
+            let branch = &paths[branch];
+            let first = branch.waypoints.first()?;
+            let rest = branch.distance_to_goal.first()?;
+            Some(position.distance(*first) + rest)
+        })
+        .reduce(f32::min)
+}
+
+/// Fills the `distance_to_goal` of every path. The distances of a path depend on its branches,
+/// so they are relaxed until nothing changes, paths that never reach a goal keep `f32::MAX`.
+fn compute_distances_to_goal(paths: &mut [EnemyPath]) {
+    for path in paths.iter_mut() {
+        path.distance_to_goal = vec![f32::MAX; path.waypoints.len()];
+    }
+
+    for _ in 0..=paths.len() {
+        let mut changed = false;
+        for index in 0..paths.len() {
+            let path = &paths[index];
+            let Some(&last) = path.waypoints.last() else {
+                continue;
+            };
+            let end = if path.branches.is_empty() {
+                0.0
+            } else {
+                shortest_branch(paths, &path.branches, last).unwrap_or(f32::MAX)
+            };
+
+            let mut distances = vec![end; path.waypoints.len()];
+            for i in (0..path.waypoints.len() - 1).rev() {
+                distances[i] = distances[i + 1] + path.waypoints[i].distance(path.waypoints[i + 1]);
+            }
+            if distances != paths[index].distance_to_goal {
+                paths[index].distance_to_goal = distances;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
+
+pub fn load_map(asset_server: Res<AssetServer>, mut commands: Commands) {
+    let handle = asset_server.load(MAP_PATH);
+    commands.insert_resource(MapDefinitionHandle(handle));
+}
+
+/// Rebuilds the `EnemyPaths` when the map is loaded or changed, the enemies already walking
+/// are moved to the new paths
+pub fn apply_map(
+    mut events: EventReader<AssetEvent<MapDefinition>>,
+    map_handle: Res<MapDefinitionHandle>,
+    maps: Res<Assets<MapDefinition>>,
+    mut enemy_paths: ResMut<EnemyPaths>,
+    mut followers: Query<(&mut PathFollower, &Transform)>,
+) {
+    let changed = events
+        .read()
+        .any(|event| event.is_added(map_handle.id()) || event.is_modified(map_handle.id()));
+    if !changed {
+        return;
+    }
+    let Some(map) = maps.get(map_handle.id()) else {
+        return;
+    };
+
+    let new_paths = EnemyPaths::from_definition(map);
+    if new_paths.paths.is_empty() {
+        warn!("the map has no paths, keeping the previous one");
+        return;
+    }
+    for (mut follower, transform) in &mut followers {
+        new_paths.remap(
+            &enemy_paths,
+            &mut follower,
+            transform.translation.truncate(),
+        );
+    }
+    *enemy_paths = new_paths;
+    info!(
+        "map loaded: {} spawns, {} paths",
+        enemy_paths.spawns.len(),
+        enemy_paths.paths.len()
+    );
+}
+
+/// The alternate forks start from their first branch on every run, so seeded runs replay the same
+pub fn reset_forks_on_game_over(mut enemy_paths: ResMut<EnemyPaths>) {
+    for path in enemy_paths.paths.iter_mut() {
+        path.forks_taken = 0;
+    }
+}
+
+/// Moves the `LeadEnemy` marker to the enemy closest to a goal
+pub fn mark_lead_enemy(
+    mut commands: Commands,
+    enemies: Query<(Entity, &DistanceToGoal, Has<LeadEnemy>), Without<Dying>>,
+) {
+    let lead = enemies
+        .iter()
+        .min_by(|(_, a, _), (_, b, _)| a.0.total_cmp(&b.0))
+        .map(|(entity, _, _)| entity);
+
+    for (entity, _, is_lead) in &enemies {
+        if Some(entity) == lead && !is_lead {
+            commands.entity(entity).insert(LeadEnemy);
+        } else if Some(entity) != lead && is_lead {
+            commands.entity(entity).remove::<LeadEnemy>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(waypoints: &[(f32, f32)], branches: Vec<usize>, fork: Fork) -> EnemyPath {
+        EnemyPath {
+            id: String::new(),
+            waypoints: waypoints.iter().map(|&(x, y)| Vec2::new(x, y)).collect(),
+            branches,
+            fork,
+            forks_taken: 0,
+            distance_to_goal: Vec::new(),
+        }
+    }
+
+    fn enemy_paths(mut paths: Vec<EnemyPath>) -> EnemyPaths {
+        compute_distances_to_goal(&mut paths);
+        EnemyPaths {
+            paths,
+            spawns: Vec::new(),
+        }
+    }
+
+    fn follower(path: usize, waypoint: usize) -> PathFollower {
+        PathFollower {
+            path,
+            waypoint,
+            ..default()
+        }
+    }
+
+    /// A path that forks at (10, 0) into a long branch to the right and a short one going up
+    fn forked_paths() -> EnemyPaths {
+        enemy_paths(vec![
+            path(&[(0.0, 0.0), (10.0, 0.0)], vec![1, 2], Fork::Alternate),
+            path(&[(10.0, 0.0), (40.0, 0.0)], vec![], Fork::Random),
+            path(&[(10.0, 0.0), (10.0, 10.0)], vec![], Fork::Random),
+        ])
+    }
+
+    #[test]
+    fn step_equal_to_the_remaining_distance_stops_on_the_waypoint() {
+        let mut paths = enemy_paths(vec![path(
+            &[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)],
+            vec![],
+            Fork::Random,
+        )]);
+        let mut follower = follower(0, 1);
+        let mut position = Vec2::ZERO;
+
+        let direction = paths.advance(&mut follower, &mut position, 10.0);
+        assert_eq!(position, Vec2::new(10.0, 0.0));
+        assert_eq!(direction, Vec2::X);
+        assert_eq!(follower.waypoint, 2);
+        assert_eq!(follower.traveled, 10.0);
+    }
+
+    #[test]
+    fn step_crossing_a_waypoint_turns_the_corner() {
+        let mut paths = enemy_paths(vec![path(
+            &[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)],
+            vec![],
+            Fork::Random,
+        )]);
+        let mut follower = follower(0, 1);
+        let mut position = Vec2::ZERO;
+
+        let direction = paths.advance(&mut follower, &mut position, 15.0);
+        assert_eq!(position, Vec2::new(10.0, 5.0));
+        assert_eq!(direction, Vec2::Y);
+        assert_eq!(follower.waypoint, 2);
+        assert_eq!(follower.traveled, 15.0);
+    }
+
+    #[test]
+    fn reaching_the_last_waypoint_stops_at_the_goal() {
+        let mut paths = enemy_paths(vec![path(
+            &[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)],
+            vec![],
+            Fork::Random,
+        )]);
+        let mut follower = follower(0, 1);
+        let mut position = Vec2::ZERO;
+
+        paths.advance(&mut follower, &mut position, 100.0);
+        assert_eq!(position, Vec2::new(10.0, 10.0));
+        assert_eq!(follower.traveled, 20.0);
+        assert!(paths.reached_goal(&follower));
+    }
+
+    #[test]
+    fn fork_takes_a_branch() {
+        let mut paths = forked_paths();
+
+        let mut first = follower(0, 1);
+        let mut position = Vec2::ZERO;
+        paths.advance(&mut first, &mut position, 15.0);
+        assert_eq!(first.path, 1);
+        assert_eq!(position, Vec2::new(15.0, 0.0));
+
+        // alternate forks send the next enemy down the other branch
+        let mut second = follower(0, 1);
+        let mut position = Vec2::ZERO;
+        let direction = paths.advance(&mut second, &mut position, 15.0);
+        assert_eq!(second.path, 2);
+        assert_eq!(position, Vec2::new(10.0, 5.0));
+        assert_eq!(direction, Vec2::Y);
+        assert_eq!(paths.paths[0].forks_taken, 2);
+    }
+
+    #[test]
+    fn random_fork_only_depends_on_the_fork_seed() {
+        let mut paths = forked_paths();
+        paths.paths[0].fork = Fork::Random;
+
+        let take_fork = |paths: &mut EnemyPaths, fork_seed: u64| {
+            let mut follower = PathFollower {
+                fork_seed,
+                ..follower(0, 1)
+            };
+            let mut position = Vec2::ZERO;
+            paths.advance(&mut follower, &mut position, 15.0);
+            follower.path
+        };
+        for fork_seed in 0..10 {
+            let branch = take_fork(&mut paths, fork_seed);
+            assert!(branch == 1 || branch == 2);
+            assert_eq!(take_fork(&mut paths, fork_seed), branch);
+        }
+    }
+
+    #[test]
+    fn distances_follow_the_shortest_branch() {
+        let paths = forked_paths();
+        assert_eq!(paths.paths[1].distance_to_goal, vec![30.0, 0.0]);
+        assert_eq!(paths.paths[2].distance_to_goal, vec![10.0, 0.0]);
+        assert_eq!(paths.paths[0].distance_to_goal, vec![20.0, 10.0]);
+    }
+
+    #[test]
+    fn distances_are_computed_whatever_the_order_of_the_paths() {
+        // every path comes before its branch, so the goal is only known after a few passes
+        let paths = enemy_paths(vec![
+            path(&[(0.0, 0.0), (5.0, 0.0)], vec![1], Fork::Random),
+            path(&[(5.0, 0.0), (10.0, 0.0)], vec![2], Fork::Random),
+            path(&[(10.0, 0.0), (10.0, 10.0)], vec![], Fork::Random),
+        ]);
+        assert_eq!(paths.paths[0].distance_to_goal, vec![20.0, 15.0]);
+    }
+
+    #[test]
+    fn paths_that_never_reach_a_goal_keep_the_max_distance() {
+        let paths = enemy_paths(vec![path(
+            &[(0.0, 0.0), (10.0, 0.0)],
+            vec![0],
+            Fork::Random,
+        )]);
+        assert_eq!(paths.paths[0].distance_to_goal, vec![f32::MAX, f32::MAX]);
+    }
+
+    #[test]
+    fn distance_at_a_fork_uses_the_shortest_branch() {
+        let paths = forked_paths();
+        let at_fork = follower(0, 2);
+        assert_eq!(paths.distance_to_goal(&at_fork, Vec2::new(10.0, 0.0)), 10.0);
+
+        let walking = follower(0, 1);
+        assert_eq!(paths.distance_to_goal(&walking, Vec2::new(4.0, 0.0)), 16.0);
+    }
+
+    #[test]
+    fn zero_length_branch_cycle_stops_the_enemy() {
+        let mut paths = enemy_paths(vec![
+            path(&[(0.0, 0.0), (10.0, 0.0)], vec![1], Fork::Random),
+            path(&[(10.0, 0.0)], vec![2], Fork::Random),
+            path(&[(10.0, 0.0)], vec![1], Fork::Random),
+        ]);
+        let mut follower = follower(0, 1);
+        let mut position = Vec2::ZERO;
+
+        paths.advance(&mut follower, &mut position, 15.0);
+        assert_eq!(position, Vec2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn reloaded_map_keeps_the_followers_on_their_path() {
+        let mut old = forked_paths();
+        for (path, id) in old.paths.iter_mut().zip(["trunk", "long", "short"]) {
+            path.id = id.to_string();
+        }
+        // the short branch moves first and loses a waypoint
+        let mut new = enemy_paths(vec![
+            path(&[(10.0, 0.0)], vec![], Fork::Random),
+            path(&[(0.0, 0.0), (10.0, 0.0)], vec![0], Fork::Random),
+        ]);
+        new.paths[0].id = "short".to_string();
+        new.paths[1].id = "trunk".to_string();
+
+        let mut on_short = follower(2, 1);
+        new.remap(&old, &mut on_short, Vec2::new(10.0, 5.0));
+        assert_eq!((on_short.path, on_short.waypoint), (0, 0));
+        assert!(!new.reached_goal(&on_short));
+
+        // the long branch is gone, the enemy walks to the closest waypoint
+        let mut on_long = follower(1, 1);
+        new.remap(&old, &mut on_long, Vec2::new(2.0, 1.0));
+        assert_eq!((on_long.path, on_long.waypoint), (1, 0));
+        assert!(!new.reached_goal(&on_long));
+    }
+}
@@ -0,0 +1,281 @@
+//! Status effects are applied by the tower shots and last a few seconds. They slow or stun the
+//! enemies, deal damage over time or shred their armor, and every enemy type can be immune to
+//! some of them. Enemies are tinted with the color of their strongest effect.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::*;
+
+/// Seconds between two ticks of the damage over time effects
+pub const DAMAGE_OVER_TIME_INTERVAL: f32 = 0.5;
+pub const MAX_POISON_STACKS: u8 = 5;
+pub const MAX_ARMOR_SHRED_STACKS: u8 = 3;
+/// A slow never stops an enemy, that's what the stun is for
+pub const MAX_SLOW: f32 = 0.8;
+
+/// Listed from the strongest to the weakest, the strongest active effect tints the enemy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum StatusEffectKind {
+    Stun,
+    Burn,
+    Poison,
+    Slow,
+    ArmorShred,
+}
+
+impl StatusEffectKind {
+    /// Hits of an effect that stack, the next hits only refresh its duration
+    pub fn max_stacks(&self) -> u8 {
+        match self {
+            StatusEffectKind::Poison => MAX_POISON_STACKS,
+            StatusEffectKind::ArmorShred => MAX_ARMOR_SHRED_STACKS,
+            StatusEffectKind::Stun | StatusEffectKind::Burn | StatusEffectKind::Slow => 1,
+        }
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            StatusEffectKind::Stun => Color::srgb(1.0, 1.0, 0.4),
+            StatusEffectKind::Burn => Color::srgb(1.0, 0.55, 0.3),
+            StatusEffectKind::Poison => Color::srgb(0.5, 1.0, 0.4),
+            StatusEffectKind::Slow => Color::srgb(0.5, 0.75, 1.0),
+            StatusEffectKind::ArmorShred => Color::srgb(0.75, 0.6, 0.75),
+        }
+    }
+}
+
+/// An effect carried by a shot. `strength` is the fraction of speed removed by a slow, the damage
+/// per second of a poison or burn stack, the armor removed by a shred stack and unused by a stun.
+#[derive(Debug, Clone, Copy)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    pub strength: f32,
+    /// Seconds
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveEffect {
+    pub kind: StatusEffectKind,
+    pub strength: f32,
+    pub stacks: u8,
+    pub timer: Timer,
+}
+
+#[derive(Component, Debug)]
+pub struct StatusEffects {
+    /// Effects this enemy type ignores
+    pub immunities: Vec<StatusEffectKind>,
+    pub active: Vec<ActiveEffect>,
+    pub damage_timer: Timer,
+}
+
+impl StatusEffects {
+    pub fn new(immunities: Vec<StatusEffectKind>) -> Self {
+        Self {
+            immunities,
+            active: Vec::new(),
+            damage_timer: Timer::from_seconds(DAMAGE_OVER_TIME_INTERVAL, TimerMode::Repeating),
+        }
+    }
+
+    /// Adds a stack of the effect, or refreshes it once it has all of its stacks. The strongest
+    /// hit sets the strength of all the stacks.
+    pub fn apply(&mut self, effect: &StatusEffect) {
+        if self.immunities.contains(&effect.kind) {
+            return;
+        }
+
+        match self
+            .active
+            .iter_mut()
+            .find(|active| active.kind == effect.kind)
+        {
+            Some(active) => {
+                active.stacks = (active.stacks + 1).min(effect.kind.max_stacks());
+                active.strength = active.strength.max(effect.strength);
+                active.timer = Timer::from_seconds(effect.duration, TimerMode::Once);
+            }
+            None => self.active.push(ActiveEffect {
+                kind: effect.kind,
+                strength: effect.strength,
+                stacks: 1,
+                timer: Timer::from_seconds(effect.duration, TimerMode::Once),
+            }),
+        }
+    }
+
+    fn total(&self, kind: StatusEffectKind) -> f32 {
+        self.active
+            .iter()
+            .filter(|active| active.kind == kind)
+            .map(|active| active.strength * active.stacks as f32)
+            .sum()
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.active
+            .iter()
+            .any(|active| active.kind == StatusEffectKind::Stun)
+    }
+
+    /// Multiplier of the movement and walk animation speed
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.is_stunned() {
+            return 0.0;
+        }
+        1.0 - self.total(StatusEffectKind::Slow).min(MAX_SLOW)
+    }
+
+    /// Armor ignored by the shots
+    pub fn armor_shred(&self) -> u16 {
+        self.total(StatusEffectKind::ArmorShred).round() as u16
+    }
+
+    pub fn damage_per_second(&self) -> f32 {
+        self.total(StatusEffectKind::Poison) + self.total(StatusEffectKind::Burn)
+    }
+
+    /// Color of the strongest active effect
+    pub fn tint(&self) -> Option<Color> {
+        self.active
+            .iter()
+            .map(|active| active.kind)
+            .min_by_key(|kind| *kind as u8)
+            .map(|kind| kind.tint())
+    }
+}
+
+/// Ticks the effects, removing the expired ones, and deals the damage over time. It ignores the
+/// armor but not the shield.
+pub fn update_status_effects(
+    mut enemies: Query<
+        (Entity, &mut Enemy, &mut StatusEffects, Option<&mut Shield>),
+        Without<Dying>,
+    >,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (entity, mut enemy, mut status_effects, shield) in &mut enemies {
+        for active in status_effects.active.iter_mut() {
+            active.timer.tick(time.delta());
+        }
+        status_effects
+            .active
+            .retain(|active| !active.timer.finished());
+
+        status_effects.damage_timer.tick(time.delta());
+        if !status_effects.damage_timer.just_finished() {
+            continue;
+        }
+        let damage =
+            (status_effects.damage_per_second() * DAMAGE_OVER_TIME_INTERVAL).round() as u16;
+        if damage > 0 {
+            let hit = apply_damage(
+                &mut enemy,
+                damage,
+                0,
+                shield.map(|shield| shield.into_inner()),
+            );
+            if hit.damage > 0 && !hit.killed {
+                commands.entity(entity).insert(HitFlash::default());
+            }
+        }
+    }
+}
+
+/// A hit flash shows over the status effects
+pub fn tint_enemies_with_status_effects(
+    mut enemies: Query<(&StatusEffects, Has<HitFlash>, &mut Sprite), Without<Dying>>,
+) {
+    for (status_effects, hit_flash, mut sprite) in &mut enemies {
+        sprite.color = if hit_flash {
+            HIT_FLASH_COLOR
+        } else {
+            status_effects.tint().unwrap_or(Color::WHITE)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effect(kind: StatusEffectKind, strength: f32, duration: f32) -> StatusEffect {
+        StatusEffect {
+            kind,
+            strength,
+            duration,
+        }
+    }
+
+    #[test]
+    fn stacking_effects_add_a_stack() {
+        let mut effects = StatusEffects::new(vec![]);
+        effects.apply(&effect(StatusEffectKind::Poison, 2.0, 3.0));
+        effects.apply(&effect(StatusEffectKind::Poison, 4.0, 3.0));
+
+        assert_eq!(effects.active.len(), 1);
+        assert_eq!(effects.active[0].stacks, 2);
+        // the strongest hit sets the strength of every stack
+        assert_eq!(effects.damage_per_second(), 8.0);
+    }
+
+    #[test]
+    fn single_stack_effects_are_refreshed() {
+        let mut effects = StatusEffects::new(vec![]);
+        effects.apply(&effect(StatusEffectKind::Burn, 3.0, 1.0));
+        effects.active[0]
+            .timer
+            .tick(std::time::Duration::from_secs_f32(0.5));
+        effects.apply(&effect(StatusEffectKind::Burn, 1.0, 2.0));
+
+        let burn = &effects.active[0];
+        assert_eq!(burn.stacks, 1);
+        assert_eq!(burn.strength, 3.0);
+        assert_eq!(burn.timer.duration().as_secs_f32(), 2.0);
+        assert_eq!(burn.timer.elapsed_secs(), 0.0);
+    }
+
+    #[test]
+    fn stacks_are_capped() {
+        let mut effects = StatusEffects::new(vec![]);
+        for _ in 0..MAX_POISON_STACKS + 2 {
+            effects.apply(&effect(StatusEffectKind::Poison, 1.0, 3.0));
+        }
+        assert_eq!(effects.active[0].stacks, MAX_POISON_STACKS);
+        assert_eq!(effects.damage_per_second(), MAX_POISON_STACKS as f32);
+    }
+
+    #[test]
+    fn immune_enemies_ignore_the_effect() {
+        let mut effects = StatusEffects::new(vec![StatusEffectKind::Stun]);
+        effects.apply(&effect(StatusEffectKind::Stun, 0.0, 1.0));
+
+        assert!(effects.active.is_empty());
+        assert!(!effects.is_stunned());
+        assert_eq!(effects.speed_multiplier(), 1.0);
+    }
+
+    #[test]
+    fn armor_shred_adds_up_the_stacks() {
+        let mut effects = StatusEffects::new(vec![]);
+        effects.apply(&effect(StatusEffectKind::ArmorShred, 2.5, 3.0));
+        effects.apply(&effect(StatusEffectKind::ArmorShred, 2.5, 3.0));
+        assert_eq!(effects.armor_shred(), 5);
+    }
+
+    #[test]
+    fn slows_are_capped_and_stuns_stop_the_enemy() {
+        let mut effects = StatusEffects::new(vec![]);
+        effects.apply(&effect(StatusEffectKind::Slow, 0.25, 3.0));
+        assert_eq!(effects.speed_multiplier(), 0.75);
+
+        effects.apply(&effect(StatusEffectKind::Slow, 0.95, 3.0));
+        assert!((effects.speed_multiplier() - (1.0 - MAX_SLOW)).abs() < f32::EPSILON);
+
+        effects.apply(&effect(StatusEffectKind::Stun, 0.0, 1.0));
+        assert_eq!(effects.speed_multiplier(), 0.0);
+    }
+}
@@ -0,0 +1,103 @@
+//! Waves are scripted in the `assets/waves/default.waves.ron` file. Every wave is a list of spawn
+//! groups played one after the other, so a wave can mix enemies, pause between groups or end
+//! with a single tough enemy acting as a boss. Like the enemy definitions, it's hot reloaded.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::*;
+
+pub const WAVE_SCRIPT_PATH: &str = "waves/default.waves.ron";
+pub const WAVE_SCRIPT_EXTENSIONS: &[&str] = &["waves.ron"];
+
+fn default_health_multiplier() -> f32 {
+    1.0
+}
+
+/// A batch of the same enemy spawned at a fixed interval
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnGroup {
+    /// `id` of the enemy in the enemy definitions
+    pub enemy: String,
+    pub count: u16,
+    /// Seconds between two spawns of the group
+    pub interval: f32,
+    /// Seconds to wait before the first spawn, counted from the end of the previous group
+    #[serde(default)]
+    pub delay: f32,
+    /// Applied on top of the life the enemy has in this wave
+    #[serde(default = "default_health_multiplier")]
+    pub health_multiplier: f32,
+    /// `id` of the spawn in the map, the first spawn when it's not set
+    #[serde(default)]
+    pub spawn: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaveDefinition {
+    pub groups: Vec<SpawnGroup>,
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct WaveScript {
+    pub waves: Vec<WaveDefinition>,
+}
+
+#[derive(Resource, Debug, Deref)]
+pub struct WaveScriptHandle(pub Handle<WaveScript>);
+
+pub fn load_wave_script(asset_server: Res<AssetServer>, mut commands: Commands) {
+    let handle = asset_server.load(WAVE_SCRIPT_PATH);
+    commands.insert_resource(WaveScriptHandle(handle));
+}
+
+/// Replaces the waves of the `WaveControl` when the script is loaded or changed
+pub fn apply_wave_script(
+    mut events: EventReader<AssetEvent<WaveScript>>,
+    script_handle: Res<WaveScriptHandle>,
+    scripts: Res<Assets<WaveScript>>,
+    mut wave_control: ResMut<WaveControl>,
+) {
+    let changed = events
+        .read()
+        .any(|event| event.is_added(script_handle.id()) || event.is_modified(script_handle.id()));
+    if !changed {
+        return;
+    }
+    let Some(script) = scripts.get(script_handle.id()) else {
+        return;
+    };
+
+    wave_control.waves = script.waves.clone();
+    info!("wave script loaded: {} waves", wave_control.waves.len());
+}
+
+/// Builds a wave after the last scripted one by mixing enemies of the pool. It only depends on the
+/// wave number, and life and speed keep scaling in `spawn_wave` like in any other wave.
+pub fn endless_wave(wave_count: u8, enemy_types: &[EnemyType]) -> WaveDefinition {
+    // bosses only show up where the script puts them
+    let pool: Vec<&EnemyType> = enemy_types
+        .iter()
+        .filter(|enemy_type| enemy_type.boss.is_none())
+        .collect();
+    if pool.is_empty() {
+        return WaveDefinition { groups: Vec::new() };
+    }
+
+    let wave = wave_count as usize;
+    let group_count = 2 + wave % 3;
+    let interval = (ENDLESS_SPAWN_INTERVAL - 0.05 * wave as f32).max(ENDLESS_MIN_SPAWN_INTERVAL);
+
+    let groups = (0..group_count)
+        .map(|group| SpawnGroup {
+            enemy: pool[(wave * 3 + group * 2) % pool.len()].id.clone(),
+            count: ENDLESS_ENEMIES_PER_WAVE / group_count as u16,
+            interval,
+            delay: ENDLESS_SPAWN_INTERVAL,
+            health_multiplier: default_health_multiplier(),
+            spawn: None,
+        })
+        .collect();
+
+    WaveDefinition { groups }
+}
@@ -56,4 +56,18 @@ pub struct Variables {
     payment_wallet: &'static str,
     #[default("")]
     signer_wallet_path: &'static str,
+    #[default("")]
+    payment_mint: &'static str,
+    #[default(1)]
+    payment_token_amount: u64,
+    #[default(false)]
+    economy_mode: bool,
+    #[default("")]
+    economy_treasury: &'static str,
+    #[default("")]
+    nft_collection: &'static str,
+    #[default(false)]
+    nft_lookup_mock: bool,
+    #[default(0)]
+    game_seed: u64,
 }
@@ -1,13 +1,13 @@
 use std::sync::Arc;
 
 use bevy::prelude::*;
-use solana_client::{
-    client_error::{ClientError, ClientErrorKind},
-    rpc_client::RpcClient,
-    rpc_request::RpcRequest,
-};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
 use solana_sdk::{
-    pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction::transfer,
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_instruction::transfer,
 };
 use td_program_sdk::{instructions, seeds::PLAYER_SEED, states::Player, PROGRAM_ID};
 
@@ -19,6 +19,8 @@ use super::*;
 pub struct PlayerInfo {
     pub data: Player,
     pub address: Pubkey,
+    /// The `address` is a placeholder until the PDA of the wallet is derived
+    pub has_address: bool,
 }
 
 impl Default for PlayerInfo {
@@ -30,6 +32,7 @@ impl Default for PlayerInfo {
                 authority: [0; 32],
             },
             address: Pubkey::new_unique(),
+            has_address: false,
         }
     }
 }
@@ -39,6 +42,7 @@ impl PlayerInfo {
         let seeds = [PLAYER_SEED, signer_pubkey.as_ref()];
         let (player, bump) = Pubkey::find_program_address(&seeds, &PROGRAM_ID);
         self.address = player;
+        self.has_address = true;
         (player, bump)
     }
 }
@@ -47,18 +51,74 @@ pub async fn get_unpacked_player_info(player: Pubkey, client: Arc<RpcClient>) ->
     let acc_data = client.get_account_data(&player)?;
     Player::unpack(acc_data.as_slice())
         .map(TaskResult::PlayerData)
-        .map_err(|e| ClientError {
-            request: Some(RpcRequest::GetAccountInfo),
-            kind: ClientErrorKind::Custom(format!("ProgramError: {:?}", e)),
-        })
+        .map_err(|e| SolanaError::InvalidAccountData(format!("{:?}", e)))
 }
+/// Price of a tower when it's paid with SOL
+pub const TOWER_PRICE_LAMPORTS: u64 = 100_000_000;
+
 pub async fn send_sol(signer: Arc<Keypair>, client: Arc<RpcClient>) -> ActionResult {
-    let to_pubkey = Pubkey::from_str_const(&VARIABLES.payment_wallet);
-    let lamports = 100_000_000;
-    let ix = transfer(&signer.pubkey(), &to_pubkey, lamports);
+    let to_pubkey = payment_wallet()?;
+    let ix = transfer(&signer.pubkey(), &to_pubkey, TOWER_PRICE_LAMPORTS);
     build_and_send_tx(signer, client, &[ix])
 }
 
+/// Pays for a tower with the SPL token set in `payment_mint`, or with SOL if there is none
+pub async fn pay_for_tower(signer: Arc<Keypair>, client: Arc<RpcClient>) -> ActionResult {
+    match payment_mint() {
+        Some(mint) => send_token(signer, client, mint).await,
+        None => send_sol(signer, client).await,
+    }
+}
+
+/// What a tower payment moves, SOL or the `payment_token_amount` of the payment mint
+pub fn tower_payment_amount() -> TxAmount {
+    match payment_mint() {
+        Some(_) => TxAmount::Tokens(VARIABLES.payment_token_amount),
+        None => TxAmount::Lamports(TOWER_PRICE_LAMPORTS),
+    }
+}
+
+pub async fn get_signature_statuses(
+    signatures: Vec<Signature>,
+    client: Arc<RpcClient>,
+) -> ActionResult {
+    let statuses = client.get_signature_statuses(&signatures)?.value;
+    let statuses = signatures
+        .into_iter()
+        .zip(statuses)
+        .filter_map(|(signature, status)| {
+            // `None` means the transaction is not in a block yet
+            let status = status?;
+            let tx_status = if let Some(err) = status.err {
+                TxStatus::Failed(SolanaError::from(err))
+            } else if status.satisfies_commitment(CommitmentConfig::finalized()) {
+                TxStatus::Finalized
+            } else {
+                TxStatus::Confirmed
+            };
+            Some((signature, tx_status))
+        })
+        .collect();
+    Ok(TaskResult::SignatureStatuses(statuses))
+}
+
+pub async fn get_recent_signatures(address: Pubkey, client: Arc<RpcClient>) -> ActionResult {
+    let config = GetConfirmedSignaturesForAddress2Config {
+        limit: Some(RECENT_SIGNATURES_LIMIT),
+        ..Default::default()
+    };
+    let recent = client
+        .get_signatures_for_address_with_config(&address, config)?
+        .into_iter()
+        .map(|status| RecentSignature {
+            signature: status.signature,
+            slot: status.slot,
+            failed: status.err.is_some(),
+        })
+        .collect();
+    Ok(TaskResult::RecentSignatures(recent))
+}
+
 pub async fn initialize_player(
     signer: Arc<Keypair>,
     client: Arc<RpcClient>,
@@ -76,6 +136,7 @@ pub async fn update_player_values(
     wave_count: u8,
     last_time_played: u64,
     player: Pubkey,
+    score_memo: String,
 ) -> ActionResult {
     let signer_pubkey = signer.pubkey();
     let ix = instructions::update_player_game_values(
@@ -84,7 +145,9 @@ pub async fn update_player_values(
         last_time_played,
         wave_count,
     );
-    build_and_send_tx(signer, client, &[ix])
+    // the program only stores the wave, the memo keeps how it was reached
+    let memo_ix = memo_instruction(&score_memo, &signer_pubkey);
+    build_and_send_tx(signer, client, &[ix, memo_ix])
 }
 
 pub fn update_onchain_values(
@@ -93,6 +156,7 @@ pub fn update_onchain_values(
     client: Res<SolClient>,
     time: Res<Time>,
     player_info: Res<PlayerInfo>,
+    history: Res<TxHistory>,
 ) {
     tasks.status_delay.tick(time.delta());
 
@@ -104,10 +168,21 @@ pub fn update_onchain_values(
                 .clone()
                 .get_balance(&pubkey)
                 .map(TaskResult::Balance)
+                .map_err(SolanaError::from)
         });
         tasks.add_task(get_unpacked_player_info(
             player_info.address,
             client.clone(),
         ));
+        if let Some(mint) = payment_mint() {
+            tasks.add_task(get_token_balance(pubkey, mint, client.clone()));
+        }
+        let unsettled_signatures = history.unsettled_signatures();
+        if !unsettled_signatures.is_empty() {
+            tasks.add_task(get_signature_statuses(unsettled_signatures, client.clone()));
+        }
+        if player_info.has_address {
+            tasks.add_task(get_recent_signatures(player_info.address, client.clone()));
+        }
     }
 }
@@ -1,10 +1,10 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
 use bevy::prelude::*;
 use solana_client::{rpc_client::RpcClient, rpc_config::RpcSendTransactionConfig};
-use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::{commitment_config::CommitmentLevel, pubkey::Pubkey};
 
-use crate::VARIABLES;
+use crate::{tower_building::GameState, VARIABLES};
 
 use super::*;
 
@@ -12,11 +12,35 @@ pub struct SolanaPlugin;
 
 impl Plugin for SolanaPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(SolClient(setup_solana_client()))
+        let client = setup_solana_client();
+        app.insert_resource(SolClient(client.clone()))
             .insert_resource(Wallet::default())
             .insert_resource(Tasks::default())
             .insert_resource(PlayerInfo::default())
-            .add_systems(Update, (update_onchain_values, process_tx_tasks));
+            .insert_resource(GoldLedger::default())
+            .insert_resource(TxHistory::default())
+            .insert_resource(SolanaErrors::default())
+            .insert_resource(NftUnlocks::new(client))
+            .add_systems(Startup, request_nft_unlocks)
+            .add_systems(
+                Update,
+                (
+                    update_onchain_values,
+                    process_tx_tasks,
+                    tick_conversion_cooldown,
+                ),
+            )
+            .add_systems(
+                Update,
+                withdraw_earned_gold
+                    .run_if(in_state(GameState::GameOver).or(in_state(GameState::Victory))),
+            )
+            .add_systems(
+                Update,
+                deposit_tokens_for_gold.run_if(in_state(GameState::Building)),
+            )
+            .add_systems(OnExit(GameState::GameOver), reset_earned_gold_on_new_run)
+            .add_systems(OnExit(GameState::Victory), reset_earned_gold_on_new_run);
     }
 }
 
@@ -33,6 +57,16 @@ pub const SEND_CFG: RpcSendTransactionConfig = RpcSendTransactionConfig {
     min_context_slot: None,
 };
 
+/// Treasury that receives the tower payments
+pub fn payment_wallet() -> Result<Pubkey, SolanaError> {
+    Pubkey::from_str(VARIABLES.payment_wallet).map_err(|e| {
+        SolanaError::Config(format!(
+            "invalid payment_wallet '{}': {}",
+            VARIABLES.payment_wallet, e
+        ))
+    })
+}
+
 pub fn setup_solana_client() -> Arc<RpcClient> {
     let rpc_url = VARIABLES.sol_rpc;
     Arc::new(RpcClient::new(rpc_url.to_string()))
@@ -0,0 +1,408 @@
+//! Economy mode converts the in-game `Gold` to and from the `payment_mint` token.
+//! Tokens can be deposited to the treasury to buy starting gold before the first wave, and the
+//! gold earned by killing enemies can be requested as tokens once the run is over.
+//!
+//! The game never holds the treasury keypair, so it can't pay withdrawals. A withdrawal is an
+//! unverified request: a memo signed by the player that lands in the history of the
+//! `economy_treasury`, with amounts computed by the game. Nothing on-chain records the gold of a
+//! run, so the request can't be checked and paying it is up to whoever holds the treasury.
+//! Every conversion is tracked in the `GoldLedger` until its transaction is confirmed, and
+//! deposits only credit gold once the rpc reports them as successful.
+
+use std::{future::Future, str::FromStr, sync::Arc};
+
+use bevy::prelude::*;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_instruction::transfer,
+};
+use spl_associated_token_account_client::instruction::create_associated_token_account_idempotent;
+
+use crate::{enemies::WaveControl, VARIABLES};
+
+use super::*;
+
+pub const GOLD_PER_TOKEN: u16 = 100;
+pub const MAX_TOKENS_PER_CONVERSION: u64 = 5;
+pub const CONVERSION_COOLDOWN_SECS: f32 = 30.0;
+pub const TOKENS_PER_DEPOSIT: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionKind {
+    /// Gold earned in the run requested as tokens, the request doesn't move any token
+    Withdraw,
+    /// Tokens sent to the treasury in exchange of starting gold
+    Deposit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionStatus {
+    Pending,
+    /// Sent with `skip_preflight`, it can still fail on-chain
+    Sent(Signature),
+    /// The deposit landed and its gold was credited
+    Settled(Signature),
+    /// The withdrawal request landed, it's not paid by the game
+    Requested(Signature),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Conversion {
+    pub id: u32,
+    pub kind: ConversionKind,
+    pub gold: u16,
+    pub tokens: u64,
+    pub status: ConversionStatus,
+}
+
+/// Keeps track of the gold that can be converted and every conversion made in this session.
+#[derive(Resource, Debug)]
+pub struct GoldLedger {
+    /// Economy mode is only enabled if there is a payment mint and a treasury address
+    pub enabled: bool,
+    pub treasury: Option<Pubkey>,
+    /// Bounties of the enemies killed in the current run, this is what can be withdrawn. Bonus
+    /// gold like streaks, interest or calling waves early is left out
+    pub earned_in_run: u16,
+    pub conversions: Vec<Conversion>,
+    /// Rate limit between conversions
+    pub cooldown: Timer,
+}
+
+impl Default for GoldLedger {
+    fn default() -> Self {
+        let treasury = if VARIABLES.economy_mode {
+            economy_treasury()
+        } else {
+            None
+        };
+        // the first conversion of the session doesn't need to wait
+        let mut cooldown = Timer::from_seconds(CONVERSION_COOLDOWN_SECS, TimerMode::Once);
+        let duration = cooldown.duration();
+        cooldown.tick(duration);
+
+        GoldLedger {
+            enabled: VARIABLES.economy_mode && payment_mint().is_some() && treasury.is_some(),
+            treasury,
+            earned_in_run: 0,
+            conversions: Vec::new(),
+            cooldown,
+        }
+    }
+}
+
+impl GoldLedger {
+    pub fn can_convert(&self) -> bool {
+        self.enabled && self.cooldown.finished()
+    }
+
+    /// Tokens that can be withdrawn with the gold earned in the current run
+    pub fn withdrawable_tokens(&self) -> u64 {
+        (self.earned_in_run / GOLD_PER_TOKEN) as u64
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.conversions
+            .iter()
+            .filter(|c| {
+                matches!(
+                    c.status,
+                    ConversionStatus::Pending | ConversionStatus::Sent(_)
+                )
+            })
+            .count()
+    }
+
+    fn start_conversion(&mut self, kind: ConversionKind, gold: u16, tokens: u64) -> u32 {
+        let id = self.conversions.len() as u32;
+        self.conversions.push(Conversion {
+            id,
+            kind,
+            gold,
+            tokens,
+            status: ConversionStatus::Pending,
+        });
+        self.cooldown.reset();
+        id
+    }
+
+    /// Updates the status of a conversion once its transaction is sent
+    pub fn mark_sent(&mut self, id: u32, result: &Result<Signature, SolanaError>) {
+        match result {
+            Ok(signature) => {
+                if let Some(conversion) = self.conversions.get_mut(id as usize) {
+                    conversion.status = ConversionStatus::Sent(*signature);
+                }
+            }
+            Err(err) => self.fail(id as usize, err),
+        }
+    }
+
+    /// Settles the conversion sent with `signature` and returns the gold that must be credited to
+    /// the player, only deposits confirmed with a success status give gold
+    pub fn confirm(&mut self, signature: &Signature, status: &TxStatus) -> u16 {
+        let Some(index) = self
+            .conversions
+            .iter()
+            .position(|c| c.status == ConversionStatus::Sent(*signature))
+        else {
+            return 0;
+        };
+        let conversion = &mut self.conversions[index];
+        match status {
+            TxStatus::Sent => 0,
+            TxStatus::Confirmed | TxStatus::Finalized => match conversion.kind {
+                ConversionKind::Deposit => {
+                    conversion.status = ConversionStatus::Settled(*signature);
+                    conversion.gold
+                }
+                ConversionKind::Withdraw => {
+                    conversion.status = ConversionStatus::Requested(*signature);
+                    0
+                }
+            },
+            TxStatus::Failed(err) => {
+                self.fail(index, err);
+                0
+            }
+        }
+    }
+
+    /// Marks the conversion as failed, the gold of a failed withdrawal can be requested again
+    fn fail(&mut self, index: usize, err: &SolanaError) {
+        let Some(conversion) = self.conversions.get_mut(index) else {
+            return;
+        };
+        conversion.status = ConversionStatus::Failed(err.user_message());
+        if conversion.kind == ConversionKind::Withdraw {
+            self.earned_in_run = self.earned_in_run.saturating_add(conversion.gold);
+        }
+    }
+}
+
+fn economy_treasury() -> Option<Pubkey> {
+    Pubkey::from_str(VARIABLES.economy_treasury)
+        .map_err(|e| {
+            error!(
+                "economy mode disabled, invalid economy_treasury '{}': {:?}",
+                VARIABLES.economy_treasury, e
+            )
+        })
+        .ok()
+}
+
+/// Wraps a transaction so its outcome, successful or not, ends up in the ledger
+async fn track_conversion(id: u32, action: impl Future<Output = ActionResult>) -> ActionResult {
+    let result = into_signature(action.await);
+    Ok(TaskResult::Conversion { id, result })
+}
+
+/// Sends an unverified request of `tokens` to the treasury, the amounts are the ones computed by
+/// the game. The token account of the player is created so it can be paid.
+pub async fn request_withdrawal(
+    signer: Arc<Keypair>,
+    treasury: Pubkey,
+    client: Arc<RpcClient>,
+    mint: Pubkey,
+    player: Pubkey,
+    gold: u16,
+    tokens: u64,
+) -> ActionResult {
+    let payer = signer.pubkey();
+    let memo = format!("withdraw tokens={} gold={} player={}", tokens, gold, player);
+    let instructions = [
+        create_associated_token_account_idempotent(&payer, &payer, &mint, &spl_token::id()),
+        memo_instruction(&memo, &payer),
+        // moves nothing, it puts the request in the history of the treasury
+        transfer(&payer, &treasury, 0),
+    ];
+    build_and_send_tx(signer, client, &instructions)
+}
+
+pub async fn deposit_tokens(
+    signer: Arc<Keypair>,
+    treasury: Pubkey,
+    client: Arc<RpcClient>,
+    mint: Pubkey,
+    tokens: u64,
+) -> ActionResult {
+    let payer = signer.pubkey();
+    let instructions =
+        token_transfer_instructions(&client, &payer, &payer, &treasury, &mint, tokens)?;
+    build_and_send_tx(signer, client, &instructions)
+}
+
+pub fn tick_conversion_cooldown(mut ledger: ResMut<GoldLedger>, time: Res<Time>) {
+    ledger.cooldown.tick(time.delta());
+}
+
+/// Requests the gold earned in the run as tokens, only available in the game over screen
+pub fn withdraw_earned_gold(
+    input: Res<ButtonInput<KeyCode>>,
+    mut ledger: ResMut<GoldLedger>,
+    wallet: Res<Wallet>,
+    client: Res<SolClient>,
+    mut tasks: ResMut<Tasks>,
+    player_info: Res<PlayerInfo>,
+) {
+    if !input.just_pressed(KeyCode::KeyG) || !ledger.can_convert() {
+        return;
+    }
+    let (Some(mint), Some(treasury)) = (payment_mint(), ledger.treasury) else {
+        return;
+    };
+
+    let tokens = ledger.withdrawable_tokens().min(MAX_TOKENS_PER_CONVERSION);
+    if tokens == 0 {
+        info!(
+            "not enough gold to withdraw: {}, {} gold per token",
+            ledger.earned_in_run, GOLD_PER_TOKEN
+        );
+        return;
+    }
+
+    let gold = tokens as u16 * GOLD_PER_TOKEN;
+    ledger.earned_in_run -= gold;
+    let id = ledger.start_conversion(ConversionKind::Withdraw, gold, tokens);
+    info!("requesting {} gold as {} tokens", gold, tokens);
+    tasks.add_task(track_conversion(
+        id,
+        request_withdrawal(
+            wallet.keypair.clone(),
+            treasury,
+            client.clone(),
+            mint,
+            player_info.address,
+            gold,
+            tokens,
+        ),
+    ));
+}
+
+/// Deposits tokens to the treasury to buy starting gold, only available before the first wave
+pub fn deposit_tokens_for_gold(
+    input: Res<ButtonInput<KeyCode>>,
+    mut ledger: ResMut<GoldLedger>,
+    wallet: Res<Wallet>,
+    client: Res<SolClient>,
+    mut tasks: ResMut<Tasks>,
+    wave_control: Res<WaveControl>,
+) {
+    if !input.just_pressed(KeyCode::KeyD)
+        || !ledger.can_convert()
+        || wave_control.first_wave_spawned
+    {
+        return;
+    }
+    let (Some(mint), Some(treasury)) = (payment_mint(), ledger.treasury) else {
+        return;
+    };
+
+    if wallet.token_ui_balance() < TOKENS_PER_DEPOSIT as f64 {
+        info!(
+            "not enough tokens to deposit: {}",
+            wallet.token_ui_balance()
+        );
+        return;
+    }
+
+    let gold = TOKENS_PER_DEPOSIT as u16 * GOLD_PER_TOKEN;
+    let id = ledger.start_conversion(ConversionKind::Deposit, gold, TOKENS_PER_DEPOSIT);
+    info!("depositing {} tokens for {} gold", TOKENS_PER_DEPOSIT, gold);
+    tasks.add_task(track_conversion(
+        id,
+        deposit_tokens(
+            wallet.keypair.clone(),
+            treasury,
+            client.clone(),
+            mint,
+            TOKENS_PER_DEPOSIT,
+        ),
+    ));
+}
+
+pub fn reset_earned_gold_on_new_run(mut ledger: ResMut<GoldLedger>) {
+    ledger.earned_in_run = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger(earned_in_run: u16) -> GoldLedger {
+        GoldLedger {
+            enabled: true,
+            treasury: Some(Pubkey::new_unique()),
+            earned_in_run,
+            conversions: Vec::new(),
+            cooldown: Timer::default(),
+        }
+    }
+
+    #[test]
+    fn deposits_credit_gold_once_confirmed() {
+        let mut ledger = ledger(0);
+        let id = ledger.start_conversion(ConversionKind::Deposit, 100, 1);
+        let signature = Signature::new_unique();
+
+        ledger.mark_sent(id, &Ok(signature));
+        assert_eq!(ledger.confirm(&signature, &TxStatus::Sent), 0);
+        assert_eq!(ledger.confirm(&signature, &TxStatus::Confirmed), 100);
+        assert_eq!(
+            ledger.conversions[0].status,
+            ConversionStatus::Settled(signature)
+        );
+        // later statuses of the same signature don't credit it again
+        assert_eq!(ledger.confirm(&signature, &TxStatus::Finalized), 0);
+    }
+
+    #[test]
+    fn confirmed_withdrawals_are_only_requested() {
+        let mut ledger = ledger(0);
+        let id = ledger.start_conversion(ConversionKind::Withdraw, 100, 1);
+        let signature = Signature::new_unique();
+
+        ledger.mark_sent(id, &Ok(signature));
+        assert_eq!(ledger.confirm(&signature, &TxStatus::Finalized), 0);
+        assert_eq!(
+            ledger.conversions[0].status,
+            ConversionStatus::Requested(signature)
+        );
+        assert_eq!(ledger.pending_count(), 0);
+    }
+
+    #[test]
+    fn failed_withdrawals_give_the_gold_back() {
+        let mut ledger = ledger(50);
+        let id = ledger.start_conversion(ConversionKind::Withdraw, 100, 1);
+        ledger.mark_sent(id, &Err(SolanaError::BlockhashExpired));
+        assert_eq!(ledger.earned_in_run, 150);
+
+        let id = ledger.start_conversion(ConversionKind::Withdraw, 100, 1);
+        let signature = Signature::new_unique();
+        ledger.mark_sent(id, &Ok(signature));
+        let status = TxStatus::Failed(SolanaError::InsufficientFunds);
+        assert_eq!(ledger.confirm(&signature, &status), 0);
+        assert_eq!(ledger.earned_in_run, 250);
+        assert!(matches!(
+            ledger.conversions[1].status,
+            ConversionStatus::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn failed_deposits_give_no_gold() {
+        let mut ledger = ledger(0);
+        let id = ledger.start_conversion(ConversionKind::Deposit, 100, 1);
+        let signature = Signature::new_unique();
+        ledger.mark_sent(id, &Ok(signature));
+
+        let status = TxStatus::Failed(SolanaError::InsufficientFunds);
+        assert_eq!(ledger.confirm(&signature, &status), 0);
+        assert_eq!(ledger.earned_in_run, 0);
+    }
+}
@@ -0,0 +1,159 @@
+//! Every Solana failure ends up as a `SolanaError`, so the game never panics when the rpc,
+//! the wallet or the program fail, and the UI can show a message the player understands.
+
+use std::{collections::VecDeque, fmt};
+
+use bevy::prelude::*;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::RpcError,
+};
+use solana_sdk::{
+    instruction::InstructionError, message::CompileError, program_error::ProgramError,
+    signer::SignerError, transaction::TransactionError,
+};
+use spl_token::error::TokenError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolanaError {
+    /// The rpc couldn't be reached or answered with an unexpected error
+    Rpc(String),
+    InsufficientFunds,
+    BlockhashExpired,
+    AccountNotFound,
+    /// Custom error code returned by a program
+    ProgramError(u32),
+    /// An account exists but its data couldn't be unpacked
+    InvalidAccountData(String),
+    /// The transaction couldn't be compiled or signed
+    Signing(String),
+    /// Something is wrong in the `cfg.toml`
+    Config(String),
+}
+
+impl SolanaError {
+    /// Short message to show to the player
+    pub fn user_message(&self) -> String {
+        match self {
+            SolanaError::Rpc(_) => "Couldn't reach Solana, check your connection.".to_string(),
+            SolanaError::InsufficientFunds => {
+                "Not enough funds in your wallet to pay for this.".to_string()
+            }
+            SolanaError::BlockhashExpired => {
+                "The transaction expired before landing, try again.".to_string()
+            }
+            SolanaError::AccountNotFound => {
+                "Account not found, it may not be initialized yet.".to_string()
+            }
+            SolanaError::ProgramError(code) => {
+                format!("The transaction was rejected (error code {}).", code)
+            }
+            SolanaError::InvalidAccountData(_) => {
+                "Couldn't read the on-chain account data.".to_string()
+            }
+            SolanaError::Signing(_) => {
+                "The transaction couldn't be signed, check your wallet keypair.".to_string()
+            }
+            SolanaError::Config(message) => format!("Invalid cfg.toml: {}.", message),
+        }
+    }
+}
+
+impl fmt::Display for SolanaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolanaError::Rpc(message)
+            | SolanaError::InvalidAccountData(message)
+            | SolanaError::Signing(message) => {
+                write!(f, "{} ({})", self.user_message(), message)
+            }
+            _ => write!(f, "{}", self.user_message()),
+        }
+    }
+}
+
+impl std::error::Error for SolanaError {}
+
+impl From<TransactionError> for SolanaError {
+    fn from(err: TransactionError) -> Self {
+        match err {
+            TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. }
+            | TransactionError::InstructionError(_, InstructionError::InsufficientFunds) => {
+                SolanaError::InsufficientFunds
+            }
+            // the token program reports a balance too low for a payment with this code
+            TransactionError::InstructionError(_, InstructionError::Custom(code))
+                if code == TokenError::InsufficientFunds as u32 =>
+            {
+                SolanaError::InsufficientFunds
+            }
+            TransactionError::BlockhashNotFound => SolanaError::BlockhashExpired,
+            TransactionError::AccountNotFound => SolanaError::AccountNotFound,
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                SolanaError::ProgramError(code)
+            }
+            err => SolanaError::Rpc(err.to_string()),
+        }
+    }
+}
+
+impl From<ClientError> for SolanaError {
+    fn from(err: ClientError) -> Self {
+        if let Some(tx_err) = err.get_transaction_error() {
+            return tx_err.into();
+        }
+        match err.kind {
+            ClientErrorKind::SigningError(signer_err) => signer_err.into(),
+            // `get_account_data` and friends report missing accounts like this
+            ClientErrorKind::RpcError(RpcError::ForUser(message))
+                if message.starts_with("AccountNotFound") =>
+            {
+                SolanaError::AccountNotFound
+            }
+            kind => SolanaError::Rpc(kind.to_string()),
+        }
+    }
+}
+
+impl From<SignerError> for SolanaError {
+    fn from(err: SignerError) -> Self {
+        SolanaError::Signing(err.to_string())
+    }
+}
+
+impl From<CompileError> for SolanaError {
+    fn from(err: CompileError) -> Self {
+        SolanaError::Signing(err.to_string())
+    }
+}
+
+impl From<ProgramError> for SolanaError {
+    fn from(err: ProgramError) -> Self {
+        match err {
+            ProgramError::Custom(code) => SolanaError::ProgramError(code),
+            err => SolanaError::InvalidAccountData(err.to_string()),
+        }
+    }
+}
+
+/// Errors waiting to be shown to the player, the UI drains this queue into toasts
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct SolanaErrors(pub VecDeque<SolanaError>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_insufficient_funds_is_not_a_program_error() {
+        let err = TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::InsufficientFunds as u32),
+        );
+        assert_eq!(SolanaError::from(err), SolanaError::InsufficientFunds);
+
+        let err = TransactionError::InstructionError(0, InstructionError::Custom(42));
+        assert_eq!(SolanaError::from(err), SolanaError::ProgramError(42));
+    }
+}
@@ -0,0 +1,190 @@
+//! History of the transactions sent by the game in this session, plus the latest signatures
+//! involving the `Player` PDA fetched from the rpc.
+
+use std::future::Future;
+
+use bevy::prelude::*;
+use solana_sdk::signature::Signature;
+
+use crate::VARIABLES;
+
+use super::*;
+
+/// Amount of signatures fetched for the `Player` PDA
+pub const RECENT_SIGNATURES_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxAction {
+    InitializePlayer,
+    TowerPayment,
+    UpdatePlayer,
+    Withdraw,
+    Deposit,
+}
+
+impl TxAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TxAction::InitializePlayer => "Initialize player",
+            TxAction::TowerPayment => "Tower payment",
+            TxAction::UpdatePlayer => "Update player",
+            TxAction::Withdraw => "Gold withdraw",
+            TxAction::Deposit => "Token deposit",
+        }
+    }
+}
+
+/// What a transaction moved out of the wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxAmount {
+    Nothing,
+    Lamports(u64),
+    /// Whole tokens of the `payment_mint`
+    Tokens(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Sent to the rpc, but not seen in a block yet
+    Sent,
+    Confirmed,
+    Finalized,
+    Failed(SolanaError),
+}
+
+impl TxStatus {
+    pub fn is_settled(&self) -> bool {
+        matches!(self, TxStatus::Finalized | TxStatus::Failed(_))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SentTx {
+    /// `None` if the transaction failed before being sent
+    pub signature: Option<Signature>,
+    pub action: TxAction,
+    pub amount: TxAmount,
+    pub status: TxStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecentSignature {
+    pub signature: String,
+    pub slot: u64,
+    pub failed: bool,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct TxHistory {
+    /// Transactions sent by the game in this session, oldest first
+    pub session: Vec<SentTx>,
+    /// Latest signatures of the `Player` PDA, newest first
+    pub recent: Vec<RecentSignature>,
+}
+
+impl TxHistory {
+    pub fn record(
+        &mut self,
+        action: TxAction,
+        amount: TxAmount,
+        result: &Result<Signature, SolanaError>,
+    ) {
+        let (signature, status) = match result {
+            Ok(signature) => (Some(*signature), TxStatus::Sent),
+            Err(err) => (None, TxStatus::Failed(err.clone())),
+        };
+        self.session.push(SentTx {
+            signature,
+            action,
+            amount,
+            status,
+        });
+    }
+
+    /// Signatures whose status can still change
+    pub fn unsettled_signatures(&self) -> Vec<Signature> {
+        self.session
+            .iter()
+            .filter(|tx| !tx.status.is_settled())
+            .filter_map(|tx| tx.signature)
+            .collect()
+    }
+
+    pub fn update_status(&mut self, signature: &Signature, status: TxStatus) {
+        if let Some(tx) = self
+            .session
+            .iter_mut()
+            .find(|tx| tx.signature.as_ref() == Some(signature))
+        {
+            tx.status = status;
+        }
+    }
+}
+
+/// Cluster the `sol_rpc` points to, used to build the explorer links
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Testnet,
+    Localnet,
+}
+
+impl Cluster {
+    pub fn from_rpc_url(rpc_url: &str) -> Self {
+        if rpc_url.contains("devnet") {
+            Cluster::Devnet
+        } else if rpc_url.contains("testnet") {
+            Cluster::Testnet
+        } else if rpc_url.contains("localhost") || rpc_url.contains("127.0.0.1") {
+            Cluster::Localnet
+        } else {
+            Cluster::Mainnet
+        }
+    }
+
+    pub fn explorer_url(&self, signature: &str) -> String {
+        let query = match self {
+            Cluster::Mainnet => String::new(),
+            Cluster::Devnet => "?cluster=devnet".to_string(),
+            Cluster::Testnet => "?cluster=testnet".to_string(),
+            // the explorer needs the address of the local validator
+            Cluster::Localnet => format!(
+                "?cluster=custom&customUrl={}",
+                encode_query_value(VARIABLES.sol_rpc)
+            ),
+        };
+        format!("https://explorer.solana.com/tx/{}{}", signature, query)
+    }
+}
+
+/// Percent encodes everything but the unreserved characters of a url
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+pub fn active_cluster() -> Cluster {
+    Cluster::from_rpc_url(VARIABLES.sol_rpc)
+}
+
+/// Wraps a transaction so it ends up in the `TxHistory`, whether it was sent or not
+pub async fn track_tx(
+    action: TxAction,
+    amount: TxAmount,
+    transaction: impl Future<Output = ActionResult>,
+) -> ActionResult {
+    let result = into_signature(transaction.await);
+    Ok(TaskResult::SentTx {
+        action,
+        amount,
+        result,
+    })
+}
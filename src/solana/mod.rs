@@ -1,11 +1,21 @@
 pub mod config;
+pub mod economy;
+pub mod error;
 pub mod wallet;
+pub mod history;
+pub mod nft;
 pub mod tasks;
 pub mod transactions;
+pub mod token;
 pub mod actions;
 
 pub use actions::*;
 pub use config::*;
+pub use economy::*;
+pub use error::*;
 pub use wallet::*;
+pub use history::*;
+pub use nft::*;
 pub use tasks::*;
 pub use transactions::*;
+pub use token::*;
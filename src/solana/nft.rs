@@ -0,0 +1,332 @@
+//! NFT gated content. Holding an NFT of the `nft_collection` set in the `cfg.toml` tints the
+//! towers with the `NFT_SKIN_COLOR` and unlocks the bonus `TowerType::Spectre`, which uses the
+//! Necro sprites for now.
+//!
+//! An NFT is a token account with an amount of 1 (and 0 decimals) whose mint has a Metaplex metadata
+//! account with a verified collection. The lookup is behind the `NftLookup` trait, so it can be
+//! replaced by the `MockNftLookup` (`nft_lookup_mock = true`) to play or test offline.
+
+use std::{str::FromStr, sync::Arc};
+
+use bevy::prelude::*;
+use solana_account_decoder_client_types::UiAccountData;
+use solana_client::{rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use crate::VARIABLES;
+
+use super::*;
+
+pub const METADATA_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+pub const METADATA_SEED: &[u8] = b"metadata";
+/// Max amount of accounts the rpc returns in a single `getMultipleAccounts` request
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+/// Tint applied to the towers of the players holding the collection
+pub const NFT_SKIN_COLOR: Color = Color::srgb(1.0, 0.85, 0.35);
+
+pub trait NftLookup: Send + Sync {
+    /// Returns the verified collections of every NFT held by `owner`
+    fn owned_collections(&self, owner: &Pubkey) -> Result<Vec<Pubkey>, SolanaError>;
+}
+
+pub struct RpcNftLookup(pub Arc<RpcClient>);
+
+impl NftLookup for RpcNftLookup {
+    fn owned_collections(&self, owner: &Pubkey) -> Result<Vec<Pubkey>, SolanaError> {
+        let token_accounts = self
+            .0
+            .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(spl_token::id()))?;
+
+        let metadata_accounts: Vec<Pubkey> = token_accounts
+            .iter()
+            .filter_map(|keyed_account| match &keyed_account.account.data {
+                UiAccountData::Json(parsed_account) => {
+                    let info = &parsed_account.parsed["info"];
+                    let token_amount = &info["tokenAmount"];
+                    let is_nft = token_amount["amount"].as_str() == Some("1")
+                        && token_amount["decimals"].as_u64() == Some(0);
+                    if !is_nft {
+                        return None;
+                    }
+                    info["mint"]
+                        .as_str()
+                        .and_then(|mint| Pubkey::from_str(mint).ok())
+                }
+                _ => None,
+            })
+            .map(|mint| metadata_address(&mint))
+            .collect();
+
+        let mut collections = Vec::new();
+        for chunk in metadata_accounts.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.0.get_multiple_accounts(chunk)?;
+            collections.extend(
+                accounts
+                    .iter()
+                    .flatten()
+                    .filter_map(|account| parse_verified_collection(&account.data)),
+            );
+        }
+        Ok(collections)
+    }
+}
+
+/// Offline lookup that pretends the wallet holds NFTs of the given collections
+pub struct MockNftLookup(pub Vec<Pubkey>);
+
+impl NftLookup for MockNftLookup {
+    fn owned_collections(&self, _owner: &Pubkey) -> Result<Vec<Pubkey>, SolanaError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Content unlocked by holding an NFT of the configured collection.
+#[derive(Resource, Clone)]
+pub struct NftUnlocks {
+    /// Collection that unlocks the content, `None` if there is no `nft_collection` in the `cfg.toml`
+    pub collection: Option<Pubkey>,
+    pub holds_collection: bool,
+    pub lookup: Arc<dyn NftLookup>,
+}
+
+impl NftUnlocks {
+    pub fn new(client: Arc<RpcClient>) -> Self {
+        let collection = if VARIABLES.nft_collection.is_empty() {
+            None
+        } else {
+            Pubkey::from_str(VARIABLES.nft_collection)
+                .map_err(|e| {
+                    error!(
+                        "invalid nft collection '{}': {:?}",
+                        VARIABLES.nft_collection, e
+                    )
+                })
+                .ok()
+        };
+        let lookup: Arc<dyn NftLookup> = if VARIABLES.nft_lookup_mock {
+            Arc::new(MockNftLookup(collection.into_iter().collect()))
+        } else {
+            Arc::new(RpcNftLookup(client))
+        };
+        NftUnlocks {
+            collection,
+            holds_collection: false,
+            lookup,
+        }
+    }
+
+    /// Updates the holdings with the collections found in the wallet
+    pub fn set_owned_collections(&mut self, collections: &[Pubkey]) {
+        self.holds_collection = self
+            .collection
+            .is_some_and(|collection| collections.contains(&collection));
+    }
+
+    pub fn bonus_tower_unlocked(&self) -> bool {
+        self.holds_collection
+    }
+
+    /// Color of the tower sprites, holders get the gold tint of the collection
+    pub fn tower_skin(&self) -> Color {
+        if self.holds_collection {
+            NFT_SKIN_COLOR
+        } else {
+            Color::WHITE
+        }
+    }
+}
+
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    let seeds = [METADATA_SEED, METADATA_PROGRAM_ID.as_ref(), mint.as_ref()];
+    Pubkey::find_program_address(&seeds, &METADATA_PROGRAM_ID).0
+}
+
+/// Minimal borsh reader, just enough to walk through the Metaplex metadata layout
+struct MetadataReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl MetadataReader<'_> {
+    fn skip(&mut self, len: usize) -> Option<()> {
+        let end = self.offset.checked_add(len)?;
+        if end > self.data.len() {
+            return None;
+        }
+        self.offset = end;
+        Some(())
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        let value = *self.data.get(self.offset)?;
+        self.offset += 1;
+        Some(value)
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let bytes = self.data.get(self.offset..self.offset + 4)?;
+        self.offset += 4;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    fn read_pubkey(&mut self) -> Option<Pubkey> {
+        let bytes = self.data.get(self.offset..self.offset + 32)?;
+        self.offset += 32;
+        Pubkey::try_from(bytes).ok()
+    }
+
+    fn skip_string(&mut self) -> Option<()> {
+        let len = self.read_u32()? as usize;
+        self.skip(len)
+    }
+
+    fn skip_option(&mut self, len: usize) -> Option<()> {
+        match self.read_u8()? {
+            0 => Some(()),
+            _ => self.skip(len),
+        }
+    }
+}
+
+/// Returns the collection of a Metaplex metadata account, only if the collection is verified
+pub fn parse_verified_collection(data: &[u8]) -> Option<Pubkey> {
+    let mut reader = MetadataReader { data, offset: 0 };
+    // key + update authority + mint
+    reader.skip(1 + 32 + 32)?;
+    // name, symbol and uri
+    reader.skip_string()?;
+    reader.skip_string()?;
+    reader.skip_string()?;
+    // seller fee basis points
+    reader.skip(2)?;
+    // creators: each one is a pubkey + verified flag + share
+    if reader.read_u8()? == 1 {
+        let creators = reader.read_u32()? as usize;
+        reader.skip(creators * 34)?;
+    }
+    // primary sale happened + is mutable
+    reader.skip(2)?;
+    // edition nonce and token standard
+    reader.skip_option(1)?;
+    reader.skip_option(1)?;
+    // collection
+    if reader.read_u8()? != 1 {
+        return None;
+    }
+    let verified = reader.read_u8()? == 1;
+    let collection = reader.read_pubkey()?;
+    verified.then_some(collection)
+}
+
+pub async fn get_owned_collections(owner: Pubkey, lookup: Arc<dyn NftLookup>) -> ActionResult {
+    lookup
+        .owned_collections(&owner)
+        .map(TaskResult::OwnedCollections)
+}
+
+pub fn request_nft_unlocks(
+    wallet: Res<Wallet>,
+    unlocks: Res<NftUnlocks>,
+    mut tasks: ResMut<Tasks>,
+) {
+    if unlocks.collection.is_none() {
+        return;
+    }
+    tasks.add_task(get_owned_collections(
+        wallet.keypair.pubkey(),
+        unlocks.lookup.clone(),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Metaplex metadata with a creator and an edition nonce, cut after the collection
+    fn metadata(collection: Option<(bool, Pubkey)>) -> Vec<u8> {
+        let mut data = vec![4];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        for text in ["Tower", "TWR", "https://example.com/tower.json"] {
+            data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            data.extend_from_slice(text.as_bytes());
+        }
+        data.extend_from_slice(&500u16.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&[1, 100]);
+        data.extend_from_slice(&[0, 1]);
+        data.extend_from_slice(&[1, 255]);
+        data.push(0);
+        match collection {
+            Some((verified, key)) => {
+                data.extend_from_slice(&[1, verified as u8]);
+                data.extend_from_slice(key.as_ref());
+            }
+            None => data.push(0),
+        }
+        data
+    }
+
+    fn unlocks(collection: Pubkey, held: Vec<Pubkey>) -> NftUnlocks {
+        NftUnlocks {
+            collection: Some(collection),
+            holds_collection: false,
+            lookup: Arc::new(MockNftLookup(held)),
+        }
+    }
+
+    fn lookup_collections(unlocks: &mut NftUnlocks) {
+        let collections = unlocks
+            .lookup
+            .owned_collections(&Pubkey::new_unique())
+            .unwrap();
+        unlocks.set_owned_collections(&collections);
+    }
+
+    #[test]
+    fn verified_collection_is_parsed() {
+        let collection = Pubkey::new_unique();
+        assert_eq!(
+            parse_verified_collection(&metadata(Some((true, collection)))),
+            Some(collection)
+        );
+    }
+
+    #[test]
+    fn unverified_collection_is_ignored() {
+        let collection = Pubkey::new_unique();
+        assert_eq!(
+            parse_verified_collection(&metadata(Some((false, collection)))),
+            None
+        );
+        assert_eq!(parse_verified_collection(&metadata(None)), None);
+    }
+
+    #[test]
+    fn truncated_metadata_is_ignored() {
+        let data = metadata(Some((true, Pubkey::new_unique())));
+        for len in [0, 40, data.len() - 1] {
+            assert_eq!(parse_verified_collection(&data[..len]), None);
+        }
+    }
+
+    #[test]
+    fn holding_the_collection_unlocks_the_content() {
+        let collection = Pubkey::new_unique();
+        let mut unlocks = unlocks(collection, vec![Pubkey::new_unique(), collection]);
+        lookup_collections(&mut unlocks);
+        assert!(unlocks.bonus_tower_unlocked());
+        assert_eq!(unlocks.tower_skin(), NFT_SKIN_COLOR);
+    }
+
+    #[test]
+    fn wrong_collection_unlocks_nothing() {
+        let mut unlocks = unlocks(Pubkey::new_unique(), vec![Pubkey::new_unique()]);
+        lookup_collections(&mut unlocks);
+        assert!(!unlocks.bonus_tower_unlocked());
+        assert_eq!(unlocks.tower_skin(), Color::WHITE);
+    }
+}
@@ -4,20 +4,55 @@ use bevy::{
     prelude::*,
     tasks::{block_on, poll_once, AsyncComputeTaskPool, Task},
 };
-use solana_client::client_error::ClientError;
-use solana_sdk::signature::Signature;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use td_program_sdk::states::Player;
 
-use super::{PlayerInfo, Wallet};
+use crate::tower_building::Gold;
+
+use super::{
+    track_tx, ConversionKind, GoldLedger, NftUnlocks, PlayerInfo, RecentSignature, SolanaError,
+    SolanaErrors, TxAction, TxAmount, TxHistory, TxStatus, Wallet,
+};
 
 #[derive(Debug)]
 pub enum TaskResult {
     Balance(u64),
+    TokenBalance {
+        amount: u64,
+        decimals: u8,
+    },
     Signature(Signature),
     PlayerData(Player),
+    /// Verified collections of the NFTs held by the wallet
+    OwnedCollections(Vec<Pubkey>),
+    /// Outcome of a gold <-> token conversion tracked in the `GoldLedger`
+    Conversion {
+        id: u32,
+        result: Result<Signature, SolanaError>,
+    },
+    /// Outcome of a transaction recorded in the `TxHistory`
+    SentTx {
+        action: TxAction,
+        amount: TxAmount,
+        result: Result<Signature, SolanaError>,
+    },
+    SignatureStatuses(Vec<(Signature, TxStatus)>),
+    RecentSignatures(Vec<RecentSignature>),
 }
 
-pub type ActionResult = Result<TaskResult, ClientError>;
+pub type ActionResult = Result<TaskResult, SolanaError>;
+
+/// Extracts the signature of a sent transaction
+pub fn into_signature(result: ActionResult) -> Result<Signature, SolanaError> {
+    match result {
+        Ok(TaskResult::Signature(signature)) => Ok(signature),
+        Ok(other) => Err(SolanaError::Rpc(format!(
+            "unexpected task result: {:?}",
+            other
+        ))),
+        Err(err) => Err(err),
+    }
+}
 
 #[derive(Resource, Debug)]
 pub struct Tasks {
@@ -42,12 +77,25 @@ impl Tasks {
         let task = AsyncComputeTaskPool::get().spawn(future);
         self.pending_tasks.push_back(task);
     }
+
+    /// Same as `add_task`, but the transaction is recorded in the `TxHistory`
+    pub fn add_tx_task<F>(&mut self, action: TxAction, amount: TxAmount, future: F)
+    where
+        F: Future<Output = ActionResult> + Send + 'static,
+    {
+        self.add_task(track_tx(action, amount, future));
+    }
 }
 
 pub fn process_tx_tasks(
     mut tasks: ResMut<Tasks>,
     mut wallet: ResMut<Wallet>,
     mut player_data: ResMut<PlayerInfo>,
+    mut ledger: ResMut<GoldLedger>,
+    mut gold: ResMut<Gold>,
+    mut nft_unlocks: ResMut<NftUnlocks>,
+    mut history: ResMut<TxHistory>,
+    mut errors: ResMut<SolanaErrors>,
 ) {
     if let Some(mut task) = tasks.pending_tasks.pop_front() {
         if let Some(result) = block_on(poll_once(&mut task)) {
@@ -57,6 +105,11 @@ pub fn process_tx_tasks(
                         wallet.balance = balance;
                         info!("wallet balance updated: {} SOL", balance);
                     }
+                    TaskResult::TokenBalance { amount, decimals } => {
+                        wallet.token_balance = amount;
+                        wallet.token_decimals = decimals;
+                        info!("wallet token balance updated: {}", amount);
+                    }
                     TaskResult::Signature(sig) => {
                         info!("transaction sent, signature: {:?}", sig);
                     }
@@ -69,9 +122,58 @@ pub fn process_tx_tasks(
                             last_time_played, player.wave_reached
                         );
                     }
+                    TaskResult::OwnedCollections(collections) => {
+                        nft_unlocks.set_owned_collections(&collections);
+                        info!(
+                            "nft collection held: {}, {} collections found",
+                            nft_unlocks.holds_collection,
+                            collections.len()
+                        );
+                    }
+                    TaskResult::Conversion { id, result } => {
+                        info!("conversion {} finished: {:?}", id, result);
+                        if let Some(conversion) = ledger.conversions.get(id as usize) {
+                            let action = match conversion.kind {
+                                ConversionKind::Withdraw => TxAction::Withdraw,
+                                ConversionKind::Deposit => TxAction::Deposit,
+                            };
+                            history.record(action, TxAmount::Tokens(conversion.tokens), &result);
+                        }
+                        if let Err(err) = &result {
+                            errors.push_back(err.clone());
+                        }
+                        ledger.mark_sent(id, &result);
+                    }
+                    TaskResult::SentTx {
+                        action,
+                        amount,
+                        result,
+                    } => {
+                        info!("{} transaction: {:?}", action.label(), result);
+                        history.record(action, amount, &result);
+                        if let Err(err) = result {
+                            errors.push_back(err);
+                        }
+                    }
+                    TaskResult::SignatureStatuses(statuses) => {
+                        for (signature, status) in statuses {
+                            if let TxStatus::Failed(err) = &status {
+                                errors.push_back(err.clone());
+                            }
+                            gold.0 = gold.0.saturating_add(ledger.confirm(&signature, &status));
+                            history.update_status(&signature, status);
+                        }
+                    }
+                    TaskResult::RecentSignatures(recent) => {
+                        history.recent = recent;
+                    }
                 },
                 Err(err) => {
-                    error!("task failed: {:?}", err);
+                    error!("task failed: {}", err);
+                    // the player account doesn't exist until the game starts, no need to warn about it
+                    if err != SolanaError::AccountNotFound {
+                        errors.push_back(err);
+                    }
                 }
             }
         } else {
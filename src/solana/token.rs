@@ -0,0 +1,116 @@
+//! SPL token payments. When a `payment_mint` is set in the `cfg.toml`, towers are paid with that
+//! token instead of native SOL, the associated token accounts of the payer and the treasury
+//! (`payment_wallet`) are created on the fly if they don't exist yet.
+
+use std::{str::FromStr, sync::Arc};
+
+use bevy::prelude::*;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction, program_pack::Pack, pubkey::Pubkey, signature::Keypair,
+    signer::Signer,
+};
+use spl_associated_token_account_client::{
+    address::get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+use spl_token::{
+    instruction::transfer_checked,
+    state::{Account as TokenAccount, Mint},
+};
+
+use crate::VARIABLES;
+
+use super::*;
+
+/// Returns the mint used to pay for towers, `None` means towers are paid with SOL
+pub fn payment_mint() -> Option<Pubkey> {
+    if VARIABLES.payment_mint.is_empty() {
+        return None;
+    }
+    Pubkey::from_str(VARIABLES.payment_mint)
+        .map_err(|e| error!("invalid payment mint '{}': {:?}", VARIABLES.payment_mint, e))
+        .ok()
+}
+
+pub fn get_mint_decimals(client: &RpcClient, mint: &Pubkey) -> Result<u8, SolanaError> {
+    let mint_data = client.get_account_data(mint)?;
+    Mint::unpack(mint_data.as_slice())
+        .map(|mint| mint.decimals)
+        .map_err(SolanaError::from)
+}
+
+/// Fetches the raw amount held in the associated token account of `owner`.
+/// A token account that doesn't exist yet is reported as an empty balance.
+pub async fn get_token_balance(
+    owner: Pubkey,
+    mint: Pubkey,
+    client: Arc<RpcClient>,
+) -> ActionResult {
+    let decimals = get_mint_decimals(&client, &mint)?;
+    let token_account = get_associated_token_address(&owner, &mint);
+    let amount = match client
+        .get_account_with_commitment(&token_account, client.commitment())?
+        .value
+    {
+        Some(account) => TokenAccount::unpack(account.data.as_slice())
+            .map(|token_account| token_account.amount)?,
+        None => 0,
+    };
+    Ok(TaskResult::TokenBalance { amount, decimals })
+}
+
+/// Builds the instructions to move `amount` whole tokens from the associated token account of
+/// `from` to the one of `to`. Both accounts are created if needed, with `payer` covering the rent.
+pub fn token_transfer_instructions(
+    client: &RpcClient,
+    payer: &Pubkey,
+    from: &Pubkey,
+    to: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<Vec<Instruction>, SolanaError> {
+    let decimals = get_mint_decimals(client, mint)?;
+    let unit = 10u64.checked_pow(decimals.into()).ok_or_else(|| {
+        SolanaError::InvalidAccountData(format!("mint {} has {} decimals", mint, decimals))
+    })?;
+    let raw_amount = amount.saturating_mul(unit);
+
+    let source = get_associated_token_address(from, mint);
+    let destination = get_associated_token_address(to, mint);
+
+    // idempotent instructions, so they are no-ops when the accounts already exist
+    let create_source_ix =
+        create_associated_token_account_idempotent(payer, from, mint, &spl_token::id());
+    let create_destination_ix =
+        create_associated_token_account_idempotent(payer, to, mint, &spl_token::id());
+    let transfer_ix = transfer_checked(
+        &spl_token::id(),
+        &source,
+        mint,
+        &destination,
+        from,
+        &[],
+        raw_amount,
+        decimals,
+    )?;
+
+    Ok(vec![create_source_ix, create_destination_ix, transfer_ix])
+}
+
+pub async fn send_token(
+    signer: Arc<Keypair>,
+    client: Arc<RpcClient>,
+    mint: Pubkey,
+) -> ActionResult {
+    let payer = signer.pubkey();
+    let treasury = payment_wallet()?;
+    let instructions = token_transfer_instructions(
+        &client,
+        &payer,
+        &payer,
+        &treasury,
+        &mint,
+        VARIABLES.payment_token_amount,
+    )?;
+    build_and_send_tx(signer, client, &instructions)
+}
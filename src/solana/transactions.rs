@@ -1,10 +1,11 @@
 use std::sync::Arc;
 
-use solana_client::{client_error::ClientError, rpc_client::RpcClient};
+use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
-    instruction::Instruction,
+    instruction::{AccountMeta, Instruction},
     message::{v0::Message, VersionedMessage},
+    pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
     transaction::VersionedTransaction,
@@ -12,20 +13,44 @@ use solana_sdk::{
 
 use super::*;
 
+pub const MEMO_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Memo instruction signed by `signer`, used to store extra data next to a transaction
+pub fn memo_instruction(memo: &str, signer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: vec![AccountMeta::new_readonly(*signer, true)],
+        data: memo.as_bytes().to_vec(),
+    }
+}
+
 pub fn build_and_send_tx(
     signer: Arc<Keypair>,
     client: Arc<RpcClient>,
     instructions: &[Instruction],
-) -> Result<TaskResult, ClientError> {
+) -> ActionResult {
+    build_and_send_multisig_tx(signer, &[], client, instructions)
+}
+
+/// Same as `build_and_send_tx`, but the `co_signers` sign the transaction too.
+/// The `signer` is always the fee payer.
+pub fn build_and_send_multisig_tx(
+    signer: Arc<Keypair>,
+    co_signers: &[Arc<Keypair>],
+    client: Arc<RpcClient>,
+    instructions: &[Instruction],
+) -> ActionResult {
     let blockhash = client
         .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())?
         .0;
 
-    let compiled_message = Message::try_compile(&signer.pubkey(), instructions, &[], blockhash)
-        .expect("compile msg failed");
+    let compiled_message = Message::try_compile(&signer.pubkey(), instructions, &[], blockhash)?;
 
     let versioned_msg = VersionedMessage::V0(compiled_message);
-    let versioned_tx = VersionedTransaction::try_new(versioned_msg, &[signer])?;
+    let mut signers = vec![signer];
+    signers.extend_from_slice(co_signers);
+    let versioned_tx = VersionedTransaction::try_new(versioned_msg, signers.as_slice())?;
 
     let signature = client.send_transaction_with_config(&versioned_tx, SEND_CFG)?;
 
@@ -14,6 +14,15 @@ use super::*;
 pub struct Wallet {
     pub keypair: Arc<Keypair>,
     pub balance: u64,
+    /// Raw amount of the `payment_mint` token held by the wallet
+    pub token_balance: u64,
+    pub token_decimals: u8,
+}
+
+impl Wallet {
+    pub fn token_ui_balance(&self) -> f64 {
+        self.token_balance as f64 / 10f64.powi(self.token_decimals as i32)
+    }
 }
 
 impl Default for Wallet {
@@ -21,6 +30,8 @@ impl Default for Wallet {
         Wallet {
             keypair: load_keypair_from_file(),
             balance: 0,
+            token_balance: 0,
+            token_decimals: 0,
         }
     }
 }
@@ -1,17 +1,25 @@
-use core::f32;
-
 use bevy::prelude::*;
 
 use crate::{
-    enemies::{BreakPointLvl, Enemy, WaveControl, BREAK_POINTS},
+    enemies::{
+        apply_damage, spawn_minions, start_dying, Armor, DistanceToGoal, Dying, Enemy,
+        EnemyAnimation, Flying, HitFlash, PathFollower, Shield, SplitOnDeath, StatusEffect,
+        StatusEffects, WaveControl, SPLIT_HEALTH_MULTIPLIER,
+    },
+    solana::GoldLedger,
     tower_building::{DESPAWN_SHOT_RANGE, SHOT_HURT_DISTANCE, SHOT_SPEED},
+    ui::{spawn_floating_text, ShowDamageNumbers, DAMAGE_NUMBER_COLOR, GOLD_POPUP_COLOR},
 };
 
-use super::{Gold, Tower, TowerControl, TOWER_ATTACK_RANGE};
+use super::{
+    BountyRules, Difficulty, Gold, KillStreak, RunStats, Tower, TowerControl, TOWER_ATTACK_RANGE,
+};
 
 #[derive(Component)]
 pub struct Shot {
     pub damage: u16,
+    /// Status effects applied to the enemy hit by the shot
+    pub effects: Vec<StatusEffect>,
     pub target: Option<(Entity, Vec3)>,
     pub animation_timer: Timer,
 }
@@ -19,10 +27,8 @@ pub struct Shot {
 /// Spawns shots from towers targeting the most "dangerous" enemies.
 ///
 /// # How it works:
-/// Each tower scans for enemies within its attack range, filtering them based on their **breakpoint level**,
-/// which represents how close they are to victory. The tower prioritizes enemies with the
-/// highest breakpoint level, and if multiple enemies share the highest breakpoint level, it selects
-/// the one closest to its designated **breakpoint position**.
+/// Each tower scans for enemies within its attack range and picks the one with the lowest
+/// **distance to goal**, the distance it still has to walk to leave the map, whatever path it's on.
 /// Once a target is selected and the attack timer completes, the tower spawns a shot aimed at the enemy.
 ///
 /// # Shot Behavior:
@@ -30,7 +36,10 @@ pub struct Shot {
 /// an animation timer and uses a **texture atlas** to handle sprite animation.
 
 pub fn spawn_shots(
-    enemies: Query<(&Transform, &BreakPointLvl, Entity), (Without<Tower>, With<Enemy>)>,
+    enemies: Query<
+        (&Transform, &DistanceToGoal, Entity, Has<Flying>),
+        (Without<Tower>, With<Enemy>, Without<Dying>),
+    >,
     mut towers: Query<(&Transform, &mut Tower)>,
     mut commands: Commands,
     time: Res<Time>,
@@ -40,51 +49,24 @@ pub fn spawn_shots(
         let tower_position = tower_transform.translation;
         tower.attack_speed.tick(time.delta());
 
-        let mut target_enemy_position = None;
-        let mut closest_distance_to_target = f32::MAX;
-        // find all enemies within the tower's attack range
-        let enemies_in_range: Vec<(&Transform, &BreakPointLvl, Entity)> = enemies
+        // find the enemy within the tower's attack range closest to a goal, that the tower can hit
+        let can_hit_air = tower.tower_type.can_hit_air();
+        let closest_to_goal = enemies
             .iter()
-            .filter(|(t, _, _)| {
+            .filter(|(_, _, _, flying)| can_hit_air || !flying)
+            .filter(|(t, _, _, _)| {
                 let enemy_position = t.translation;
                 let distance = tower_position.distance(enemy_position);
                 distance < TOWER_ATTACK_RANGE && distance > 0.0
             })
-            .collect();
-
-        // identify the highest breakpoint level among the enemies in range
-        let max_break_value = enemies_in_range
-            .iter()
-            .cloned()
-            .map(|(_, b, _)| b)
-            .max()
-            .unwrap_or(&BreakPointLvl(0));
+            .min_by(|(_, a, _, _), (_, b, _, _)| a.0.total_cmp(&b.0));
 
-        // select all enemies that share this highest breakpoint level
-        let closer_enemies_to_victory: Vec<(&Transform, &BreakPointLvl, Entity)> = enemies_in_range
-            .iter()
-            .filter(|(_, b, _)| **b == *max_break_value)
-            .copied()
-            .collect();
-
-        // determine the enemy closest to its designated breakpoint
-        let mut closest_enemy = None;
-        for (enemy_transform, break_point_lvl, enemy_entity) in &closer_enemies_to_victory {
-            let index = break_point_lvl.0 as usize;
-            let enemy_position = enemy_transform.translation;
-            let distance_to_target = enemy_position.truncate().distance(BREAK_POINTS[index]);
-
-            if distance_to_target < closest_distance_to_target {
-                closest_distance_to_target = distance_to_target;
-                target_enemy_position = Some(enemy_position);
-                closest_enemy = Some(enemy_entity);
-            }
-        }
-        if let Some(enemy_position) = target_enemy_position {
+        if let Some((enemy_transform, _, enemy_entity, _)) = closest_to_goal {
             if tower.attack_speed.just_finished() {
                 let shot = Shot {
                     damage: tower.attack_damage,
-                    target: Some((*closest_enemy.unwrap(), enemy_position)),
+                    effects: tower.tower_type.status_effects(),
+                    target: Some((enemy_entity, enemy_transform.translation)),
                     animation_timer: Timer::from_seconds(0.05, TimerMode::Repeating),
                 };
                 let (texture, atlas_handle) = tower_control
@@ -112,16 +94,26 @@ pub fn spawn_shots(
 }
 
 pub fn move_shots_to_enemies(
-    mut enemies: Query<(Entity, &Transform, &mut Enemy), Without<Shot>>,
+    mut enemies: Query<
+        (
+            &Transform,
+            &mut Enemy,
+            &mut StatusEffects,
+            Option<&Armor>,
+            Option<&mut Shield>,
+        ),
+        (Without<Shot>, Without<Dying>),
+    >,
     mut shots: Query<(Entity, &mut Transform, &mut Shot, &mut Sprite)>,
     mut commands: Commands,
-    mut gold: ResMut<Gold>,
     time: Res<Time>,
-    wave_control: Res<WaveControl>,
+    show_damage_numbers: Res<ShowDamageNumbers>,
 ) {
     for (shot_entity, mut transform, mut shot, mut shot_sprite) in &mut shots {
         if let Some((target_entity, _)) = shot.target {
-            if let Ok((enemy_entity, enemy_transform, mut enemy)) = enemies.get_mut(target_entity) {
+            if let Ok((enemy_transform, mut enemy, mut status_effects, armor, shield)) =
+                enemies.get_mut(target_entity)
+            {
                 let direction = (enemy_transform.translation - transform.translation).normalize();
                 transform.translation += direction * SHOT_SPEED * time.delta_secs();
 
@@ -144,16 +136,31 @@ pub fn move_shots_to_enemies(
                         .as_ref()
                         .map_or(true, |atlas| atlas.index >= 7)
                     {
-                        enemy.life = enemy.life.saturating_sub(shot.damage);
-                        if enemy.life == 0 {
-                            commands.entity(enemy_entity).despawn();
-
-                            let wave_factor = wave_control.wave_count as f32 + 1.0;
-                            let gold_reward =
-                                ((enemy.life as f32 / 2.5) + (wave_factor * 2.0)).round() as u16;
-
-                            gold.0 += gold_reward;
-                            info!("Enemy killed! Gained {} gold.", gold_reward);
+                        // the armor shred of the previous shots applies to this one
+                        let armor = armor
+                            .map_or(0, |armor| armor.0)
+                            .saturating_sub(status_effects.armor_shred());
+                        let hit = apply_damage(
+                            &mut enemy,
+                            shot.damage,
+                            armor,
+                            shield.map(|shield| shield.into_inner()),
+                        );
+                        if show_damage_numbers.0 && hit.damage > 0 {
+                            spawn_floating_text(
+                                &mut commands,
+                                hit.damage.to_string(),
+                                enemy_transform.translation,
+                                DAMAGE_NUMBER_COLOR,
+                            );
+                        }
+                        if !hit.killed {
+                            for effect in &shot.effects {
+                                status_effects.apply(effect);
+                            }
+                            if hit.damage > 0 {
+                                commands.entity(target_entity).insert(HitFlash::default());
+                            }
                         }
 
                         commands.entity(shot_entity).despawn();
@@ -164,9 +171,72 @@ pub fn move_shots_to_enemies(
     }
 }
 
+/// Gives the gold of the enemies killed by the shots or the damage over time and starts their
+/// death, the ones that split on death spawn their minions here
+pub fn reward_killed_enemies(
+    enemies: Query<
+        (
+            Entity,
+            &Transform,
+            &PathFollower,
+            &DistanceToGoal,
+            &Enemy,
+            &EnemyAnimation,
+            Option<&SplitOnDeath>,
+        ),
+        Without<Dying>,
+    >,
+    mut commands: Commands,
+    mut gold: ResMut<Gold>,
+    mut gold_ledger: ResMut<GoldLedger>,
+    wave_control: Res<WaveControl>,
+    mut stats: ResMut<RunStats>,
+    difficulty: Res<Difficulty>,
+    bounty_rules: Res<BountyRules>,
+    mut kill_streak: ResMut<KillStreak>,
+) {
+    for (enemy_entity, enemy_transform, path_follower, distance_to_goal, enemy, animation, split) in
+        &enemies
+    {
+        if enemy.life > 0 {
+            continue;
+        }
+
+        start_dying(&mut commands, enemy_entity, animation, enemy_transform);
+        if let Some(split) = split {
+            spawn_minions(
+                &mut commands,
+                &split.enemy,
+                split.count,
+                SPLIT_HEALTH_MULTIPLIER,
+                enemy_transform.translation,
+                *path_follower,
+                &wave_control,
+                &difficulty,
+            );
+        }
+
+        let streak_kills = kill_streak.record_kill(&bounty_rules);
+        let bounty = bounty_rules.bounty(enemy.bounty, wave_control.wave_count);
+        let gold_reward = bounty.saturating_add(bounty_rules.streak_bonus(streak_kills));
+
+        gold.0 = gold.0.saturating_add(gold_reward);
+        // only the bounty can be withdrawn, the streak bonus stays in the run
+        gold_ledger.earned_in_run = gold_ledger.earned_in_run.saturating_add(bounty);
+        stats.record_kill(&enemy.id, gold_reward, distance_to_goal.0);
+        spawn_floating_text(
+            &mut commands,
+            format!("+{}", gold_reward),
+            enemy_transform.translation,
+            GOLD_POPUP_COLOR,
+        );
+        info!("Enemy killed! Gained {} gold.", gold_reward);
+    }
+}
+
 pub fn despawn_shots_with_killed_target(
     mut shots: Query<(&Shot, &mut Sprite, &mut Transform, Entity), Without<Enemy>>,
-    enemies: Query<(Entity, &Transform), With<Enemy>>,
+    enemies: Query<(Entity, &Transform), (With<Enemy>, Without<Dying>)>,
     mut commands: Commands,
     time: Res<Time>,
 ) {
@@ -0,0 +1,150 @@
+//! Gold given for the kills, defined in the `assets/economy/default.bounty.ron` file.
+//! Every enemy type has a base bounty, scaled with the wave number, and the rules can add a bonus
+//! for kill streaks and pay interest on the saved gold when a wave is cleared.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::enemies::WaveControl;
+
+use super::*;
+
+pub const BOUNTY_RULES_PATH: &str = "economy/default.bounty.ron";
+pub const BOUNTY_RULES_EXTENSIONS: &[&str] = &["bounty.ron"];
+
+/// Kills close to each other make a streak, every kill after the first one gives extra gold
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreakRules {
+    /// Max seconds between two kills of the same streak
+    pub window: f32,
+    pub gold_per_kill: u16,
+    pub max_bonus: u16,
+}
+
+/// Gold paid on the saved gold when a wave is cleared
+#[derive(Debug, Clone, Deserialize)]
+pub struct InterestRules {
+    /// Fraction of the saved gold
+    pub rate: f32,
+    pub max_gold: u16,
+}
+
+#[derive(Asset, TypePath, Resource, Debug, Clone, Deserialize)]
+pub struct BountyRules {
+    /// The bounty grows by this fraction of the base bounty with every wave
+    pub wave_multiplier: f32,
+    #[serde(default)]
+    pub streak: Option<StreakRules>,
+    #[serde(default)]
+    pub interest: Option<InterestRules>,
+}
+
+/// Used until the rules are loaded
+impl Default for BountyRules {
+    fn default() -> Self {
+        Self {
+            wave_multiplier: 1.0,
+            streak: None,
+            interest: None,
+        }
+    }
+}
+
+impl BountyRules {
+    pub fn bounty(&self, base_bounty: u16, wave_count: u8) -> u16 {
+        (base_bounty as f32 * (1.0 + self.wave_multiplier * wave_count as f32)).round() as u16
+    }
+
+    /// Extra gold of the `kills`th kill of a streak
+    pub fn streak_bonus(&self, kills: u16) -> u16 {
+        self.streak.as_ref().map_or(0, |streak| {
+            (kills.saturating_sub(1).saturating_mul(streak.gold_per_kill)).min(streak.max_bonus)
+        })
+    }
+
+    pub fn interest(&self, gold: u16) -> u16 {
+        self.interest.as_ref().map_or(0, |interest| {
+            ((gold as f32 * interest.rate).floor() as u16).min(interest.max_gold)
+        })
+    }
+}
+
+#[derive(Resource, Debug, Deref)]
+pub struct BountyRulesHandle(pub Handle<BountyRules>);
+
+#[derive(Resource, Debug, Default)]
+pub struct KillStreak {
+    pub kills: u16,
+    /// Time left to keep the streak going
+    pub timer: Timer,
+}
+
+impl KillStreak {
+    /// Counts a kill and returns the number of kills of the streak
+    pub fn record_kill(&mut self, rules: &BountyRules) -> u16 {
+        let Some(streak) = &rules.streak else {
+            return 1;
+        };
+        self.kills = if self.timer.finished() {
+            1
+        } else {
+            self.kills.saturating_add(1)
+        };
+        self.timer = Timer::from_seconds(streak.window, TimerMode::Once);
+        self.kills
+    }
+}
+
+pub fn load_bounty_rules(asset_server: Res<AssetServer>, mut commands: Commands) {
+    let handle = asset_server.load(BOUNTY_RULES_PATH);
+    commands.insert_resource(BountyRulesHandle(handle));
+}
+
+/// Replaces the `BountyRules` resource when the rules are loaded or changed
+pub fn apply_bounty_rules(
+    mut events: EventReader<AssetEvent<BountyRules>>,
+    rules_handle: Res<BountyRulesHandle>,
+    rules: Res<Assets<BountyRules>>,
+    mut commands: Commands,
+) {
+    let changed = events
+        .read()
+        .any(|event| event.is_added(rules_handle.id()) || event.is_modified(rules_handle.id()));
+    if !changed {
+        return;
+    }
+    if let Some(rules) = rules.get(rules_handle.id()) {
+        commands.insert_resource(rules.clone());
+        info!("bounty rules loaded");
+    }
+}
+
+/// A new run doesn't continue the streak of the last one
+pub fn reset_kill_streak(mut kill_streak: ResMut<KillStreak>) {
+    *kill_streak = KillStreak::default();
+}
+
+pub fn tick_kill_streak(mut kill_streak: ResMut<KillStreak>, time: Res<Time>) {
+    kill_streak.timer.tick(time.delta());
+}
+
+/// Pays the interest on the saved gold when a wave is cleared, it is a bonus so it can't be
+/// withdrawn
+pub fn pay_interest(
+    wave_control: Res<WaveControl>,
+    rules: Res<BountyRules>,
+    mut gold: ResMut<Gold>,
+    mut stats: ResMut<RunStats>,
+) {
+    // the first time to build of a run comes before any wave
+    if !wave_control.first_wave_spawned {
+        return;
+    }
+
+    let interest = rules.interest(gold.0);
+    if interest > 0 {
+        gold.0 = gold.0.saturating_add(interest);
+        stats.gold_earned += interest as u32;
+        info!("interest paid: {} gold", interest);
+    }
+}
@@ -2,12 +2,12 @@ use bevy::prelude::*;
 use bevy_ecs_tiled::prelude::*;
 
 use crate::{
-    solana::{send_sol, SolClient, Tasks, Wallet},
+    solana::{pay_for_tower, tower_payment_amount, NftUnlocks, SolClient, Tasks, TxAction, Wallet},
     tilemap::TILE_SIZE,
 };
 
 use super::{
-    Gold, Lifes, SelectedTowerType, TowerControl, TowerType, INITIAL_PLAYER_GOLD, MAX_LIFES,
+    Difficulty, Gold, Lifes, RunStats, SelectedTowerType, TowerControl, TowerType,
     TOWER_POSITION_PLACEMENT,
 };
 
@@ -37,6 +37,8 @@ pub fn buy_and_spawn_tower(
     wallet: ResMut<Wallet>,
     sol_client: Res<SolClient>,
     mut tasks: ResMut<Tasks>,
+    nft_unlocks: Res<NftUnlocks>,
+    mut stats: ResMut<RunStats>,
 ) {
     let window = windows.single();
     let range = 32.0;
@@ -86,7 +88,10 @@ pub fn buy_and_spawn_tower(
                             .get(&(selected_tower_type.0.clone(), tower_level))
                         {
                             commands.spawn((
-                                Sprite::from_image(texture.clone()),
+                                Sprite {
+                                    color: nft_unlocks.tower_skin(),
+                                    ..Sprite::from_image(texture.clone())
+                                },
                                 tower,
                                 Transform {
                                     translation: Vec3::new(placement.x, placement.y - 16.0, 1.0),
@@ -96,10 +101,16 @@ pub fn buy_and_spawn_tower(
                             ));
                             tower_control.placements[i] = 1;
                             gold.0 -= tower_cost;
+                            stats.towers_built += 1;
+                            stats.gold_spent += tower_cost as u32;
                             info!("gold: {:?}", gold.0);
                             let client = sol_client.clone();
                             let signer = wallet.keypair.clone();
-                            tasks.add_task(send_sol(signer, client));
+                            tasks.add_tx_task(
+                                TxAction::TowerPayment,
+                                tower_payment_amount(),
+                                pay_for_tower(signer, client),
+                            );
                             break;
                         }
                     }
@@ -116,6 +127,7 @@ pub fn upgrade_tower(
     tower_control: ResMut<TowerControl>,
     mut gold: ResMut<Gold>,
     mut towers: Query<(&Transform, &mut Sprite, &mut Tower)>,
+    mut stats: ResMut<RunStats>,
 ) {
     let window = windows.single();
     if let Some(cursor_position) = window.cursor_position() {
@@ -147,6 +159,8 @@ pub fn upgrade_tower(
                                     gold.0, tower.attack_damage, tower.attack_speed
                                 );
                                 gold.0 -= tower_cost;
+                                stats.towers_upgraded += 1;
+                                stats.gold_spent += tower_cost as u32;
                                 *tower = tower_info;
                                 info!(
                                     "gold after up: {:?}, tower damage after up {:?}, attack speed: {:?}",
@@ -173,6 +187,7 @@ fn is_cursor_over_entity(entity_pos: Vec2, sprite: &Sprite, cursor_pos: Vec2) ->
 pub fn select_tower_type(
     mut selected_tower_type: ResMut<SelectedTowerType>,
     input: Res<ButtonInput<KeyCode>>,
+    nft_unlocks: Res<NftUnlocks>,
 ) {
     if input.just_pressed(KeyCode::KeyW) {
         selected_tower_type.0 = TowerType::Zigurat;
@@ -183,6 +198,9 @@ pub fn select_tower_type(
     if input.just_pressed(KeyCode::KeyQ) {
         selected_tower_type.0 = TowerType::Lich;
     }
+    if input.just_pressed(KeyCode::KeyR) && nft_unlocks.bonus_tower_unlocked() {
+        selected_tower_type.0 = TowerType::Spectre;
+    }
 }
 
 #[derive(Component)]
@@ -235,13 +253,14 @@ pub fn despawn_towers_and_reset_on_game_over(
     mut gold: ResMut<Gold>,
     mut commands: Commands,
     mut lifes: ResMut<Lifes>,
+    difficulty: Res<Difficulty>,
 ) {
     for entity in &mut towers {
         commands.entity(entity).despawn();
     }
     tower_control.placements = [0; 15];
-    gold.0 = INITIAL_PLAYER_GOLD;
-    lifes.0 = MAX_LIFES;
+    gold.0 = difficulty.starting_gold;
+    lifes.0 = difficulty.starting_lifes;
 }
 
 // TODO: set the attack points based on the specific layer of the tiled map provided
@@ -4,6 +4,9 @@
 //! This file contains all the constants and resources needed for the attack and building systems.
 
 use super::*;
+use crate::enemies::{
+    update_boss_phases, update_status_effects, RonAssetLoader, StatusEffect, StatusEffectKind,
+};
 use bevy::{prelude::*, utils::hashbrown::HashMap};
 
 pub struct TowersPlugin;
@@ -14,11 +17,25 @@ impl Plugin for TowersPlugin {
             .insert_resource(Gold(INITIAL_PLAYER_GOLD))
             .insert_resource(Lifes(MAX_LIFES))
             .insert_resource(SelectedTowerType(TowerType::Lich))
-            .add_systems(Startup, load_towers_sprites)
+            .insert_resource(RunStats::default())
+            .insert_resource(Difficulty::default())
+            .insert_resource(GameRng::default())
+            .register_type::<Difficulty>()
+            .init_asset::<BountyRules>()
+            .register_asset_loader(RonAssetLoader::<BountyRules>::new(BOUNTY_RULES_EXTENSIONS))
+            .insert_resource(BountyRules::default())
+            .insert_resource(KillStreak::default())
+            .add_systems(Startup, (load_towers_sprites, load_bounty_rules))
+            .add_systems(Update, apply_bounty_rules)
+            .add_systems(OnEnter(GameState::Building), pay_interest)
             .add_systems(
                 OnEnter(GameState::GameOver),
                 despawn_towers_and_reset_on_game_over,
             )
+            .add_systems(
+                OnEnter(GameState::Victory),
+                despawn_towers_and_reset_on_game_over,
+            )
             // build systems
             .add_systems(
                 Update,
@@ -40,16 +57,50 @@ impl Plugin for TowersPlugin {
                 (
                     spawn_shots,
                     move_shots_to_enemies,
+                    reward_killed_enemies
+                        .after(move_shots_to_enemies)
+                        .after(update_status_effects)
+                        .after(update_boss_phases),
                     despawn_shots_with_killed_target,
+                    tick_kill_streak,
                 )
                     .run_if(in_state(GameState::Attacking)),
             )
-            .add_systems(OnEnter(GameState::Building), delete_all_shots_on_building);
+            .add_systems(OnEnter(GameState::Building), delete_all_shots_on_building)
+            .add_systems(
+                Update,
+                tick_run_time
+                    .run_if(in_state(GameState::Building).or(in_state(GameState::Attacking))),
+            )
+            .add_systems(
+                Update,
+                (select_difficulty, apply_starting_values).chain().run_if(
+                    in_state(GameState::Start)
+                        .or(in_state(GameState::GameOver))
+                        .or(in_state(GameState::Victory)),
+                ),
+            )
+            .add_systems(
+                OnExit(GameState::GameOver),
+                (
+                    reset_run_stats,
+                    reseed_game_rng_on_new_run,
+                    reset_kill_streak,
+                ),
+            )
+            .add_systems(
+                OnExit(GameState::Victory),
+                (
+                    reset_run_stats,
+                    reseed_game_rng_on_new_run,
+                    reset_kill_streak,
+                ),
+            );
     }
 }
 
-pub const COST_TABLE: [u16; 3] = [40, 100, 180];
-pub const INITIAL_TOWER_DAMAGE: [u16; 3] = [15, 40, 150];
+pub const COST_TABLE: [u16; 4] = [40, 100, 180, 140];
+pub const INITIAL_TOWER_DAMAGE: [u16; 4] = [15, 40, 150, 70];
 pub const TOWER_ATTACK_RANGE: f32 = 250.0;
 pub const DESPAWN_SHOT_RANGE: f32 = 1500.0;
 pub const SHOT_HURT_DISTANCE: f32 = 700.0;
@@ -81,6 +132,7 @@ pub enum GameState {
     Building,
     Attacking,
     GameOver,
+    Victory,
     Start,
     HowToPlay,
 }
@@ -117,6 +169,8 @@ pub enum TowerType {
     Lich,
     Zigurat,
     Necro,
+    /// Bonus tower, only available for the holders of the configured NFT collection
+    Spectre,
 }
 
 #[derive(Resource, Debug, Deref, DerefMut, Hash)]
@@ -130,6 +184,7 @@ impl TowerType {
             TowerType::Lich => COST_TABLE[0],
             TowerType::Zigurat => COST_TABLE[1],
             TowerType::Necro => COST_TABLE[2],
+            TowerType::Spectre => COST_TABLE[3],
         };
         if level == 1 {
             return base_cost;
@@ -137,6 +192,32 @@ impl TowerType {
         (base_cost as f32 * 1.3f32.powf(level as f32)).round() as u16
     }
 
+    /// Whether the tower shots can hit flying enemies, the necro shots are too slow for them
+    pub fn can_hit_air(&self) -> bool {
+        match self {
+            TowerType::Lich | TowerType::Zigurat | TowerType::Spectre => true,
+            TowerType::Necro => false,
+        }
+    }
+
+    /// Status effects applied by every shot of the tower
+    pub fn status_effects(&self) -> Vec<StatusEffect> {
+        let effect = |kind, strength, duration| StatusEffect {
+            kind,
+            strength,
+            duration,
+        };
+        match self {
+            TowerType::Lich => vec![effect(StatusEffectKind::Slow, 0.3, 1.5)],
+            TowerType::Zigurat => vec![effect(StatusEffectKind::Burn, 12.0, 2.0)],
+            TowerType::Necro => vec![
+                effect(StatusEffectKind::Poison, 8.0, 4.0),
+                effect(StatusEffectKind::ArmorShred, 3.0, 4.0),
+            ],
+            TowerType::Spectre => vec![effect(StatusEffectKind::Stun, 0.0, 0.3)],
+        }
+    }
+
     /// Generates the stats for a tower based on its type and level
     /// Includes attack damage and attack speed, both of which scale with level
     pub fn to_tower_data(&self, level: u8) -> TowerInfo {
@@ -144,6 +225,7 @@ impl TowerType {
             TowerType::Lich => INITIAL_TOWER_DAMAGE[0],
             TowerType::Zigurat => INITIAL_TOWER_DAMAGE[1],
             TowerType::Necro => INITIAL_TOWER_DAMAGE[2],
+            TowerType::Spectre => INITIAL_TOWER_DAMAGE[3],
         };
 
         // damage scales exponentially with level
@@ -155,6 +237,7 @@ impl TowerType {
             TowerType::Lich => 0.5,
             TowerType::Zigurat => 0.4,
             TowerType::Necro => 1.2,
+            TowerType::Spectre => 0.7,
         };
 
         // attack speed scales with level, but has a minimum cap to prevent extreme speeds
@@ -192,12 +275,17 @@ pub fn load_towers_sprites(
         ((TowerType::Necro, 1), "towers/necro_01_tower.png"),
         ((TowerType::Necro, 2), "towers/necro_01_tower.png"),
         ((TowerType::Necro, 3), "towers/necro_01_tower.png"),
+        // the spectre uses the necro sprites for now, holders see it with the nft tint
+        ((TowerType::Spectre, 1), "towers/necro_01_tower.png"),
+        ((TowerType::Spectre, 2), "towers/necro_01_tower.png"),
+        ((TowerType::Spectre, 3), "towers/necro_01_tower.png"),
     ];
 
     let tower_shots = vec![
         (TowerType::Lich, "towers/shot_lich_tower.png"),
         (TowerType::Zigurat, "towers/shot_zigurat_tower.png"),
         (TowerType::Necro, "towers/shot_necro_tower.png"),
+        (TowerType::Spectre, "towers/shot_zigurat_tower.png"),
     ];
 
     for (tower_type, shot_path) in tower_shots {
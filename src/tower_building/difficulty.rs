@@ -0,0 +1,134 @@
+//! The difficulty sets how fast enemies scale with the waves, and how much gold and lifes the
+//! player starts with. It's picked in the start screen, and the custom preset can be tweaked in
+//! the world inspector before starting a run.
+
+use bevy::prelude::*;
+
+use crate::enemies::SCALAR as ENEMY_SCALAR;
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum DifficultyPreset {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Custom,
+}
+
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct Difficulty {
+    pub preset: DifficultyPreset,
+    /// Multiplies the base life of every enemy
+    pub life_multiplier: f32,
+    /// Enemy life is multiplied by this value on every wave
+    pub life_growth: f32,
+    /// Enemy speed is multiplied by this value on every wave
+    pub speed_growth: f32,
+    pub max_speed: f32,
+    pub starting_gold: u16,
+    pub starting_lifes: u8,
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self::from_preset(DifficultyPreset::Normal)
+    }
+}
+
+impl Difficulty {
+    pub fn from_preset(preset: DifficultyPreset) -> Self {
+        let normal = Self {
+            preset,
+            life_multiplier: 1.0,
+            life_growth: 1.2 + ENEMY_SCALAR,
+            speed_growth: 1.05,
+            max_speed: 300.0,
+            starting_gold: INITIAL_PLAYER_GOLD,
+            starting_lifes: MAX_LIFES,
+        };
+        match preset {
+            DifficultyPreset::Normal | DifficultyPreset::Custom => normal,
+            DifficultyPreset::Easy => Self {
+                life_multiplier: 0.75,
+                life_growth: 1.85,
+                speed_growth: 1.04,
+                max_speed: 250.0,
+                starting_gold: 150,
+                starting_lifes: 40,
+                ..normal
+            },
+            DifficultyPreset::Hard => Self {
+                life_multiplier: 1.25,
+                life_growth: 2.0,
+                speed_growth: 1.06,
+                max_speed: 340.0,
+                starting_gold: 75,
+                starting_lifes: 20,
+                ..normal
+            },
+        }
+    }
+
+    /// How much the life of an enemy is multiplied in the given wave
+    pub fn life_scale(&self, wave_count: u8) -> f32 {
+        self.life_multiplier * self.life_growth.powf(wave_count as f32)
+    }
+
+    pub fn enemy_speed(&self, base_speed: f32, wave_count: u8) -> f32 {
+        (base_speed * self.speed_growth.powf(wave_count as f32)).min(self.max_speed)
+    }
+
+    /// Short description stored with the on-chain score, so runs on different
+    /// difficulties can be told apart
+    pub fn score_label(&self) -> String {
+        match self.preset {
+            DifficultyPreset::Custom => format!(
+                "Custom(life={}x{},speed={}<{},gold={},lifes={})",
+                self.life_multiplier,
+                self.life_growth,
+                self.speed_growth,
+                self.max_speed,
+                self.starting_gold,
+                self.starting_lifes
+            ),
+            preset => format!("{:?}", preset),
+        }
+    }
+}
+
+// the difficulty is picked with the keys 1 to 4 in the start screen, or before playing again
+pub fn select_difficulty(input: Res<ButtonInput<KeyCode>>, mut difficulty: ResMut<Difficulty>) {
+    let preset = if input.just_pressed(KeyCode::Digit1) {
+        DifficultyPreset::Easy
+    } else if input.just_pressed(KeyCode::Digit2) {
+        DifficultyPreset::Normal
+    } else if input.just_pressed(KeyCode::Digit3) {
+        DifficultyPreset::Hard
+    } else if input.just_pressed(KeyCode::Digit4) {
+        DifficultyPreset::Custom
+    } else {
+        return;
+    };
+
+    if preset == DifficultyPreset::Custom {
+        // custom keeps the current values, so they can be tweaked from any preset
+        difficulty.preset = preset;
+    } else {
+        *difficulty = Difficulty::from_preset(preset);
+    }
+}
+
+/// Starting gold and lifes follow the difficulty until the run starts
+pub fn apply_starting_values(
+    difficulty: Res<Difficulty>,
+    mut gold: ResMut<Gold>,
+    mut lifes: ResMut<Lifes>,
+) {
+    if difficulty.is_changed() {
+        gold.0 = difficulty.starting_gold;
+        lifes.0 = difficulty.starting_lifes;
+    }
+}
@@ -1,7 +1,15 @@
 pub mod attack;
+pub mod bounty;
 pub mod build;
 pub mod config;
+pub mod difficulty;
+pub mod rng;
+pub mod stats;
 
 pub use attack::*;
+pub use bounty::*;
 pub use build::*;
 pub use config::*;
+pub use difficulty::*;
+pub use rng::*;
+pub use stats::*;
@@ -0,0 +1,47 @@
+//! All the gameplay randomness comes from the seeded `GameRng`, so a run can be replayed with
+//! the same seed. The seed is shown in the game ui and stored in the memo of the on-chain score.
+//! Set `game_seed` in `cfg.toml` to replay a run, otherwise every run gets a new random seed.
+//!
+//! The rng is only drawn from in a fixed order, like once per enemy in the order they spawn, and
+//! never from systems that depend on the frame rate, which would change the draws between runs.
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::VARIABLES;
+
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct GameRng {
+    pub seed: u64,
+    #[deref]
+    pub rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// The seed of the config, or a random one when it's not set
+    pub fn from_config() -> Self {
+        let seed = match VARIABLES.game_seed {
+            0 => rand::random(),
+            seed => seed,
+        };
+        Self::new(seed)
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_config()
+    }
+}
+
+pub fn reseed_game_rng_on_new_run(mut game_rng: ResMut<GameRng>) {
+    *game_rng = GameRng::from_config();
+    info!("new run seed: {}", game_rng.seed);
+}
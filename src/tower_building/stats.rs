@@ -0,0 +1,54 @@
+//! Numbers of the current run, shown in the summary screen when the run ends with a victory
+//! or a game over. They are reset when the next run starts.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+#[derive(Resource, Debug, Default)]
+pub struct RunStats {
+    pub waves_cleared: u8,
+    /// Enemies killed by enemy id
+    pub kills: BTreeMap<String, u32>,
+    pub gold_earned: u32,
+    pub gold_spent: u32,
+    pub towers_built: u16,
+    pub towers_upgraded: u16,
+    pub lives_lost: u16,
+    /// Seconds played, the start and end screens don't count
+    pub time_played: f32,
+    /// Lowest distance to the goal an enemy was killed at
+    pub closest_kill_to_goal: Option<f32>,
+    /// Enemies that reached a goal by wave number, starting at 1
+    pub leaks: BTreeMap<u8, u16>,
+}
+
+impl RunStats {
+    pub fn record_kill(&mut self, enemy_id: &str, gold_reward: u16, distance_to_goal: f32) {
+        *self.kills.entry(enemy_id.to_string()).or_default() += 1;
+        self.gold_earned += gold_reward as u32;
+        // enemies killed before their first move don't know their distance yet
+        if distance_to_goal < f32::MAX {
+            self.closest_kill_to_goal = Some(
+                self.closest_kill_to_goal
+                    .map_or(distance_to_goal, |closest| closest.min(distance_to_goal)),
+            );
+        }
+    }
+
+    pub fn record_leak(&mut self, wave_count: u8) {
+        *self.leaks.entry(wave_count.saturating_add(1)).or_default() += 1;
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+}
+
+pub fn tick_run_time(mut stats: ResMut<RunStats>, time: Res<Time>) {
+    stats.time_played += time.delta_secs();
+}
+
+pub fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
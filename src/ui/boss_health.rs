@@ -0,0 +1,101 @@
+use bevy::{color::palettes::css::RED, prelude::*};
+
+use crate::enemies::{Boss, Dying, Enemy};
+
+use super::*;
+
+pub const BOSS_HEALTH_BAR_WIDTH: f32 = 500.0;
+
+#[derive(Component)]
+pub struct BossHealthFill;
+
+#[derive(Component)]
+pub struct BossNameText;
+
+// large health bar at the top of the screen, shown while a boss is alive
+pub fn spawn_boss_health_bar(commands: &mut Commands) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Auto,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                top: Val::Percent(3.0),
+                ..default()
+            },
+            Name::new("boss health"),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(BORDER_AND_TEXT_UI_COLOR),
+                BossNameText,
+            ));
+            p.spawn((
+                Node {
+                    width: Val::Px(BOSS_HEALTH_BAR_WIDTH),
+                    height: Val::Px(22.0),
+                    border: UiRect::all(Val::Px(3.0)),
+                    margin: UiRect::top(Val::Px(5.0)),
+                    ..default()
+                },
+                BorderColor(BORDER_AND_TEXT_UI_COLOR),
+                BorderRadius::all(Val::Px(6.0)),
+                BackgroundColor(BACKGROUND_COLOR),
+            ))
+            .with_child((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(3.0)),
+                BackgroundColor(RED.into()),
+                BossHealthFill,
+            ));
+        });
+}
+
+/// Spawns the boss health bar when a boss shows up, keeps it updated with the life of the
+/// first boss alive, and removes it once no boss is left
+pub fn update_boss_health_bar(
+    mut commands: Commands,
+    bosses: Query<(&Enemy, &Boss), Without<Dying>>,
+    entities: Query<(Entity, &Name), With<Node>>,
+    mut fills: Query<&mut Node, With<BossHealthFill>>,
+    mut names: Query<&mut Text, With<BossNameText>>,
+) {
+    let health_bar = entities
+        .iter()
+        .find(|(_, name)| name.as_str() == "boss health")
+        .map(|(entity, _)| entity);
+
+    let Some((enemy, boss)) = bosses.iter().next() else {
+        if let Some(entity) = health_bar {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    };
+
+    if health_bar.is_none() {
+        spawn_boss_health_bar(&mut commands);
+        return;
+    }
+
+    let life_fraction = enemy.life as f32 / enemy.max_life.max(1) as f32;
+    for mut fill in &mut fills {
+        fill.width = Val::Percent(life_fraction * 100.0);
+    }
+    for mut text in &mut names {
+        text.0 = format!(
+            "{} - {}/{}",
+            boss.definition.name, enemy.life, enemy.max_life
+        );
+    }
+}
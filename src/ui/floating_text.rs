@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+pub const FLOATING_TEXT_DURATION_SECS: f32 = 0.8;
+pub const FLOATING_TEXT_RISE_SPEED: f32 = 40.0;
+pub const FLOATING_TEXT_Z: f32 = 4.0;
+pub const DAMAGE_NUMBER_COLOR: Color = Color::srgb(1.0, 0.95, 0.8);
+pub const GOLD_POPUP_COLOR: Color = Color::srgb(1.0, 0.8, 0.1);
+
+/// Whether damage numbers are shown when shots land, toggled with the V key
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct ShowDamageNumbers(pub bool);
+
+impl Default for ShowDamageNumbers {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// World-space text that rises and fades out, like damage numbers
+#[derive(Component, Debug)]
+pub struct FloatingText {
+    pub timer: Timer,
+}
+
+pub fn spawn_floating_text(commands: &mut Commands, text: String, position: Vec3, color: Color) {
+    commands.spawn((
+        Text2d::new(text),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(color),
+        Transform::from_xyz(position.x, position.y, FLOATING_TEXT_Z),
+        FloatingText {
+            timer: Timer::from_seconds(FLOATING_TEXT_DURATION_SECS, TimerMode::Once),
+        },
+    ));
+}
+
+pub fn animate_floating_texts(
+    mut commands: Commands,
+    mut texts: Query<(Entity, &mut Transform, &mut TextColor, &mut FloatingText)>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, mut color, mut floating_text) in &mut texts {
+        floating_text.timer.tick(time.delta());
+        if floating_text.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += FLOATING_TEXT_RISE_SPEED * time.delta_secs();
+        color.0.set_alpha(1.0 - floating_text.timer.fraction());
+    }
+}
+
+pub fn toggle_damage_numbers(
+    input: Res<ButtonInput<KeyCode>>,
+    mut show_damage_numbers: ResMut<ShowDamageNumbers>,
+) {
+    if input.just_pressed(KeyCode::KeyV) {
+        show_damage_numbers.0 = !show_damage_numbers.0;
+    }
+}
@@ -3,7 +3,22 @@ use bevy::{
     prelude::*,
 };
 
-pub fn spawn_game_over_ui(mut commands: Commands) {
+use crate::{
+    enemies::GameMode,
+    solana::GoldLedger,
+    tower_building::{Difficulty, GameRng, RunStats},
+};
+
+use super::*;
+
+pub fn spawn_game_over_ui(
+    mut commands: Commands,
+    ledger: Res<GoldLedger>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    stats: Res<RunStats>,
+    game_rng: Res<GameRng>,
+) {
     let root_ui = commands
         .spawn((
             Node {
@@ -51,6 +66,24 @@ pub fn spawn_game_over_ui(mut commands: Commands) {
     let _message = create_text(&mut commands, "Try again, you can do it!", 15.0);
     add_top_padding(&mut commands, root_ui, 25.0);
 
+    spawn_run_summary(&mut commands, root_ui, &stats, game_rng.seed);
+    add_top_padding(&mut commands, root_ui, 25.0);
+
+    spawn_run_settings_texts(&mut commands, root_ui, *game_mode, &difficulty);
+    add_top_padding(&mut commands, root_ui, 25.0);
+
+    if ledger.enabled {
+        let _withdraw_message = create_text(
+            &mut commands,
+            &format!(
+                "Press G to request the {} gold earned in this run as tokens, the game doesn't pay it.",
+                ledger.earned_in_run
+            ),
+            15.0,
+        );
+        add_top_padding(&mut commands, root_ui, 25.0);
+    }
+
     let _button = commands.entity(root_ui).with_children(|parent| {
         parent
             .spawn((
@@ -2,9 +2,9 @@ use bevy::prelude::*;
 use solana_sdk::{native_token::LAMPORTS_PER_SOL, signer::Signer};
 
 use crate::{
-    enemies::WaveControl,
-    solana::Wallet,
-    tower_building::{GameState, Gold, Lifes},
+    enemies::{game_over, WaveControl},
+    solana::{payment_mint, Wallet},
+    tower_building::{GameRng, GameState, Gold, Lifes},
 };
 
 use super::*;
@@ -17,21 +17,60 @@ pub enum TextType {
     WaveCountText,
     LifesText,
     WalletBalanceText,
+    TokenBalanceText,
     WalletAddressText,
+    SeedText,
 }
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_sign_message_to_start)
+        app.insert_resource(ShowDamageNumbers::default())
+            .add_systems(Startup, spawn_sign_message_to_start)
+            .add_systems(Startup, spawn_toast_container)
+            .add_systems(Startup, spawn_life_loss_flash)
             .add_systems(OnExit(GameState::Start), spawn_how_to_play_ui)
-            .add_systems(OnExit(GameState::HowToPlay), spawn_game_ui)
+            .add_systems(
+                OnExit(GameState::HowToPlay),
+                (spawn_game_ui, spawn_gold_ledger_ui),
+            )
             .add_systems(OnEnter(GameState::GameOver), spawn_game_over_ui)
-            .add_systems(OnEnter(GameState::Building), spawn_tower_selected_text)
-            .add_systems(OnExit(GameState::Building), despawn_selected_tower_ui)
-            .add_systems(Update, (handle_btn_interaction, update_ui_texts))
+            .add_systems(OnEnter(GameState::Victory), spawn_victory_ui)
+            .add_systems(
+                OnEnter(GameState::Building),
+                (spawn_tower_selected_text, spawn_wave_preview_ui),
+            )
+            .add_systems(
+                OnExit(GameState::Building),
+                (despawn_selected_tower_ui, despawn_wave_preview_ui),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_btn_interaction,
+                    update_ui_texts,
+                    update_gold_ledger_text,
+                    toggle_gold_ledger_ui,
+                    toggle_tx_history_ui,
+                    update_tx_history_text,
+                    show_error_toasts,
+                    despawn_expired_toasts,
+                    update_game_mode_text,
+                    update_difficulty_text,
+                    update_boss_health_bar,
+                    animate_floating_texts,
+                    toggle_damage_numbers,
+                    // the last lifes are lost in the frame the game over is set
+                    update_life_loss_flash.after(game_over),
+                ),
+            )
             .add_systems(
                 Update,
-                update_tower_selected_text.run_if(in_state(GameState::Building)),
+                (
+                    update_tower_selected_text,
+                    handle_next_wave_btn,
+                    update_wave_preview_text,
+                )
+                    .run_if(in_state(GameState::Building)),
             );
     }
 }
@@ -108,6 +147,8 @@ pub fn spawn_game_ui(mut commands: Commands, wallet: Res<Wallet>) {
 
     let _lifes_text = create_text(&mut commands, "Lifes: 30", TextType::LifesText, 10.0);
 
+    let _seed_text = create_text(&mut commands, "Seed: 0", TextType::SeedText, 10.0);
+
     let _sol_balance_text = create_text(
         &mut commands,
         "Sol Balance: 0.0",
@@ -115,6 +156,15 @@ pub fn spawn_game_ui(mut commands: Commands, wallet: Res<Wallet>) {
         10.0,
     );
 
+    if payment_mint().is_some() {
+        let _token_balance_text = create_text(
+            &mut commands,
+            "Token Balance: 0.0",
+            TextType::TokenBalanceText,
+            10.0,
+        );
+    }
+
     let wallet_str = wallet.keypair.pubkey().to_string();
     let shortened_wallet = format!(
         "{}...{}",
@@ -133,22 +183,32 @@ pub fn spawn_game_ui(mut commands: Commands, wallet: Res<Wallet>) {
 // Update in real-time the UI texts with the resources states
 pub fn update_ui_texts(
     mut texts: Query<(&mut Text, &TextType)>,
-    resources: (Res<Gold>, Res<Lifes>, Res<Wallet>, Res<WaveControl>),
+    resources: (
+        Res<Gold>,
+        Res<Lifes>,
+        Res<Wallet>,
+        Res<WaveControl>,
+        Res<GameRng>,
+    ),
 ) {
-    let (gold, lifes, wallet, wave_control) = resources;
+    let (gold, lifes, wallet, wave_control, game_rng) = resources;
     for (mut text, text_type) in &mut texts {
         match text_type {
             TextType::GoldText => text.0 = format!("Gold: {:?}", gold.0),
             TextType::WaveCountText => {
-                text.0 = format!("Wave count: {}", wave_control.wave_count + 1)
+                text.0 = format!("Wave count: {}", wave_control.wave_count.saturating_add(1))
             }
             TextType::LifesText => text.0 = format!("Lifes: {:?}", lifes.0),
+            TextType::SeedText => text.0 = format!("Seed: {}", game_rng.seed),
             TextType::WalletBalanceText => {
                 text.0 = format!(
                     "Sol Balance: {:.2}",
                     wallet.balance as f32 / LAMPORTS_PER_SOL as f32
                 )
             }
+            TextType::TokenBalanceText => {
+                text.0 = format!("Token Balance: {:.2}", wallet.token_ui_balance())
+            }
             TextType::WalletAddressText => {
                 // here we can add logic to update the text wallet address if the wallet change in any time
             }
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::solana::{
+    ConversionKind, ConversionStatus, GoldLedger, GOLD_PER_TOKEN, TOKENS_PER_DEPOSIT,
+};
+
+use super::*;
+
+/// Amount of conversions listed in the ledger, older ones are still kept in the `GoldLedger`
+pub const MAX_LEDGER_ENTRIES: usize = 5;
+
+#[derive(Component)]
+pub struct GoldLedgerRoot;
+
+#[derive(Component)]
+pub struct GoldLedgerText;
+
+// ledger of pending and settled gold <-> token conversions, only spawned in economy mode
+pub fn spawn_gold_ledger_ui(mut commands: Commands, ledger: Res<GoldLedger>) {
+    if !ledger.enabled {
+        return;
+    }
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Px(280.0),
+                height: Val::Auto,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                position_type: PositionType::Absolute,
+                left: Val::Percent(3.0),
+                border: UiRect::all(Val::Px(5.0)),
+                top: Val::Percent(60.0),
+                ..default()
+            },
+            BorderColor(BORDER_AND_TEXT_UI_COLOR),
+            BorderRadius::all(Val::Px(15.0)),
+            // keep it visible on top of the game over screen, where withdrawals are made
+            GlobalZIndex(1),
+            Visibility::Visible,
+            Name::new("gold ledger"),
+            BackgroundColor(BACKGROUND_COLOR),
+            GoldLedgerRoot,
+        ))
+        .with_child((
+            Text::new("Gold Ledger"),
+            TextFont {
+                font_size: 13.0,
+                ..default()
+            },
+            TextColor(BORDER_AND_TEXT_UI_COLOR),
+            GoldLedgerText,
+        ));
+}
+
+pub fn update_gold_ledger_text(
+    mut texts: Query<&mut Text, With<GoldLedgerText>>,
+    ledger: Res<GoldLedger>,
+) {
+    for mut text in &mut texts {
+        let mut lines = vec![
+            "Gold Ledger (L to hide)".to_string(),
+            format!(
+                "D: deposit {} token for {} gold before wave 1",
+                TOKENS_PER_DEPOSIT,
+                TOKENS_PER_DEPOSIT as u16 * GOLD_PER_TOKEN
+            ),
+            "G: send an unverified withdrawal request after game over".to_string(),
+            format!(
+                "Earned this run: {} gold ({} tokens)",
+                ledger.earned_in_run,
+                ledger.withdrawable_tokens()
+            ),
+            format!("Pending conversions: {}", ledger.pending_count()),
+        ];
+        if !ledger.cooldown.finished() {
+            lines.push(format!(
+                "Next conversion in {:.0} secs",
+                ledger.cooldown.remaining_secs()
+            ));
+        }
+
+        for conversion in ledger.conversions.iter().rev().take(MAX_LEDGER_ENTRIES) {
+            let kind = match conversion.kind {
+                ConversionKind::Withdraw => "Withdraw",
+                ConversionKind::Deposit => "Deposit",
+            };
+            let status = match &conversion.status {
+                ConversionStatus::Pending => "pending",
+                ConversionStatus::Sent(_) => "confirming",
+                ConversionStatus::Settled(_) => "settled",
+                ConversionStatus::Requested(_) => "requested, not paid by the game",
+                ConversionStatus::Failed(_) => "failed",
+            };
+            lines.push(format!(
+                "#{} {}: {} gold / {} tokens - {}",
+                conversion.id, kind, conversion.gold, conversion.tokens, status
+            ));
+        }
+
+        text.0 = lines.join("\n");
+    }
+}
+
+pub fn toggle_gold_ledger_ui(
+    input: Res<ButtonInput<KeyCode>>,
+    mut roots: Query<&mut Visibility, With<GoldLedgerRoot>>,
+) {
+    if !input.just_pressed(KeyCode::KeyL) {
+        return;
+    }
+    for mut visibility in &mut roots {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
@@ -67,21 +67,28 @@ pub fn spawn_how_to_play_ui(mut commands: Commands) {
 
     let _explaining = create_text(
         &mut commands,
-        "Key Q - Lich Tower: Cheap and reliable.",
+        "Key Q - Lich Tower: Cheap and reliable, slows enemies down.",
         15.0,
         10.0,
     );
 
     let _explaining = create_text(
         &mut commands,
-        "Key W - Zigurat Tower: Hits fast, keeps up the pressure.",
+        "Key W - Zigurat Tower: Hits fast, keeps up the pressure and burns enemies.",
         15.0,
         10.0,
     );
 
     let _explaining = create_text(
         &mut commands,
-        "Key E - Necro Tower: Slow but deals heavy damage.",
+        "Key E - Necro Tower: Slow but deals heavy damage, poisons and shreds armor, can't hit flying enemies.",
+        15.0,
+        10.0,
+    );
+
+    let _explaining = create_text(
+        &mut commands,
+        "Key R - Spectre Tower: Unlocked by holding an NFT of the partner collection, stuns enemies. Holders also get gold tinted towers.",
         15.0,
         35.0,
     );
@@ -109,7 +116,21 @@ pub fn spawn_how_to_play_ui(mut commands: Commands) {
 
     let _explaining = create_text(
         &mut commands,
-        "Defeat enemies to earn gold and spend it on new towers or upgrades.",
+        "Defeat enemies to earn gold and spend it on new towers or upgrades, kill streaks and saved gold pay extra.",
+        15.0,
+        10.0,
+    );
+
+    let _explaining = create_text(
+        &mut commands,
+        "Press Enter while building to call the next wave early, skipped seconds give bonus gold.",
+        15.0,
+        10.0,
+    );
+
+    let _explaining = create_text(
+        &mut commands,
+        "Press V to show or hide the damage numbers.",
         15.0,
         10.0,
     );
@@ -169,7 +190,7 @@ pub fn handle_btn_interaction(
 
                 if let Some((entity, _)) = entities
                     .iter()
-                    .find(|(_, name)| name.as_str() == "game over")
+                    .find(|(_, name)| name.as_str() == "game over" || name.as_str() == "victory")
                 {
                     game_state.set(GameState::Building);
                     entity_to_despawn = Some(entity);
@@ -191,7 +212,11 @@ pub fn handle_btn_interaction(
                     let signer = wallet.keypair.clone();
                     let signer_pubkey = signer.pubkey();
                     let (player, bump) = player_info.set_address(&signer_pubkey);
-                    tasks.add_task(initialize_player(signer, client.clone(), player, bump));
+                    tasks.add_tx_task(
+                        TxAction::InitializePlayer,
+                        TxAmount::Nothing,
+                        initialize_player(signer, client.clone(), player, bump),
+                    );
                     game_state.set(GameState::Building);
                     entity_to_despawn = Some(entity);
                 }
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::tower_building::{GameState, Lifes};
+
+pub const LIFE_LOSS_FLASH_SECS: f32 = 0.5;
+pub const LIFE_LOSS_FLASH_ALPHA: f32 = 0.15;
+/// Extra alpha for every other life lost at once, so leaking a boss flashes stronger
+pub const LIFE_LOSS_FLASH_ALPHA_PER_LIFE: f32 = 0.05;
+pub const LIFE_LOSS_FLASH_MAX_ALPHA: f32 = 0.5;
+pub const LIFE_LOSS_FLASH_COLOR: Srgba = Srgba::rgb(0.8, 0.0, 0.0);
+
+#[derive(Component)]
+pub struct LifeLossFlash {
+    pub alpha: f32,
+    pub timer: Timer,
+}
+
+// red overlay over the whole screen, transparent until lifes are lost
+pub fn spawn_life_loss_flash(mut commands: Commands) {
+    let mut timer = Timer::from_seconds(LIFE_LOSS_FLASH_SECS, TimerMode::Once);
+    timer.tick(timer.duration());
+
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        PickingBehavior::IGNORE,
+        GlobalZIndex(2),
+        Name::new("life loss flash"),
+        LifeLossFlash { alpha: 0.0, timer },
+    ));
+}
+
+/// Starts the flash when the lifes go down while playing and fades it out. The lifes also change
+/// in the menus when the starting values are applied, those changes don't flash.
+pub fn update_life_loss_flash(
+    mut flashes: Query<(&mut LifeLossFlash, &mut BackgroundColor)>,
+    lifes: Res<Lifes>,
+    mut last_lifes: Local<Option<u8>>,
+    state: Res<State<GameState>>,
+    time: Res<Time>,
+) {
+    let playing = matches!(state.get(), GameState::Building | GameState::Attacking);
+    let lost = match *last_lifes {
+        Some(last) if playing => last.saturating_sub(lifes.0),
+        _ => 0,
+    };
+    *last_lifes = Some(lifes.0);
+
+    for (mut flash, mut background) in &mut flashes {
+        if lost > 0 {
+            flash.alpha = (LIFE_LOSS_FLASH_ALPHA
+                + (lost - 1) as f32 * LIFE_LOSS_FLASH_ALPHA_PER_LIFE)
+                .min(LIFE_LOSS_FLASH_MAX_ALPHA);
+            flash.timer.reset();
+        }
+        flash.timer.tick(time.delta());
+        let alpha = flash.alpha * (1.0 - flash.timer.fraction());
+        background.0 = LIFE_LOSS_FLASH_COLOR.with_alpha(alpha).into();
+    }
+}
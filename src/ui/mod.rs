@@ -1,11 +1,29 @@
+pub mod boss_health;
+pub mod floating_text;
 pub mod game_values;
+pub mod gold_ledger;
 pub mod how_to_play;
+pub mod life_loss_flash;
+pub mod run_summary;
 pub mod sign_message;
+pub mod toast;
 pub mod tower_selected;
+pub mod tx_history;
+pub mod victory;
+pub mod wave_preview;
 pub mod game_over;
 
 pub use game_over::*;
 pub use tower_selected::*;
+pub use tx_history::*;
+pub use victory::*;
+pub use wave_preview::*;
+pub use boss_health::*;
+pub use floating_text::*;
 pub use game_values::*;
+pub use gold_ledger::*;
 pub use how_to_play::*;
+pub use life_loss_flash::*;
+pub use run_summary::*;
 pub use sign_message::*;
+pub use toast::*;
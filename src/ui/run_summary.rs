@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+
+use crate::tower_building::RunStats;
+
+use super::*;
+
+pub fn run_summary_lines(stats: &RunStats, seed: u64) -> Vec<String> {
+    let minutes = (stats.time_played / 60.0) as u32;
+    let seconds = (stats.time_played % 60.0) as u32;
+
+    let mut lines = vec![
+        format!("Waves cleared: {}", stats.waves_cleared),
+        format!("Enemies killed: {}", stats.total_kills()),
+    ];
+    for (enemy_id, kills) in &stats.kills {
+        lines.push(format!("  {}: {}", enemy_id, kills));
+    }
+    lines.push(format!(
+        "Gold earned: {} - Gold spent: {}",
+        stats.gold_earned, stats.gold_spent
+    ));
+    lines.push(format!(
+        "Towers built: {} - Towers upgraded: {}",
+        stats.towers_built, stats.towers_upgraded
+    ));
+    lines.push(format!("Lifes lost: {}", stats.lives_lost));
+    if !stats.leaks.is_empty() {
+        let leaks: Vec<String> = stats
+            .leaks
+            .iter()
+            .map(|(wave, leaks)| format!("wave {}: {}", wave, leaks))
+            .collect();
+        lines.push(format!("Leaks: {}", leaks.join(", ")));
+    }
+    if let Some(distance) = stats.closest_kill_to_goal {
+        lines.push(format!("Closest kill to the goal: {:.0} px", distance));
+    }
+    lines.push(format!("Time played: {:02}:{:02}", minutes, seconds));
+    lines.push(format!("Seed: {}", seed));
+    lines
+}
+
+// summary of the run, shown inside the game over and victory screens
+pub fn spawn_run_summary(commands: &mut Commands, parent: Entity, stats: &RunStats, seed: u64) {
+    commands.entity(parent).with_children(|p| {
+        p.spawn((
+            Node {
+                width: Val::Px(420.0),
+                height: Val::Auto,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                border: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BorderColor(BORDER_AND_TEXT_UI_COLOR),
+            BorderRadius::all(Val::Px(15.0)),
+            BackgroundColor(BACKGROUND_COLOR),
+            Name::new("run summary"),
+        ))
+        .with_child((
+            Text::new(run_summary_lines(stats, seed).join("\n")),
+            TextFont {
+                font_size: 15.0,
+                ..default()
+            },
+            TextColor(BORDER_AND_TEXT_UI_COLOR),
+        ));
+    });
+}
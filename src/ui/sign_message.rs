@@ -4,11 +4,66 @@ use bevy::{
 };
 use solana_sdk::signer::Signer;
 
-use crate::solana::*;
+use crate::{enemies::GameMode, solana::*, tower_building::Difficulty};
+
+#[derive(Component)]
+pub struct GameModeText;
+
+#[derive(Component)]
+pub struct DifficultyText;
+
+pub fn game_mode_text(game_mode: GameMode) -> String {
+    format!("Mode: {} (press M to change)", game_mode.label())
+}
+
+pub fn difficulty_text(difficulty: &Difficulty) -> String {
+    format!(
+        "Difficulty: {:?} (press 1 Easy, 2 Normal, 3 Hard, 4 Custom)",
+        difficulty.preset
+    )
+}
+
+// game mode and difficulty of the next run, shown in the start and end screens
+pub fn spawn_run_settings_texts(
+    commands: &mut Commands,
+    root_ui: Entity,
+    game_mode: GameMode,
+    difficulty: &Difficulty,
+) {
+    commands.entity(root_ui).with_children(|p| {
+        p.spawn((
+            Text::new(game_mode_text(game_mode)),
+            TextFont {
+                font_size: 15.0,
+                ..default()
+            },
+            TextColor(WHITE.into()),
+            GameModeText,
+        ));
+        p.spawn(Node {
+            height: Val::Px(10.0),
+            ..default()
+        });
+        p.spawn((
+            Text::new(difficulty_text(difficulty)),
+            TextFont {
+                font_size: 15.0,
+                ..default()
+            },
+            TextColor(WHITE.into()),
+            DifficultyText,
+        ));
+    });
+}
 
 // this UI is the **start ui** to sign the message with the keypair and change
 // the `GameState` to start playing.
-pub fn spawn_sign_message_to_start(mut commands: Commands, wallet: Res<Wallet>) {
+pub fn spawn_sign_message_to_start(
+    mut commands: Commands,
+    wallet: Res<Wallet>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+) {
     let root_ui = commands
         .spawn((
             Node {
@@ -60,6 +115,9 @@ pub fn spawn_sign_message_to_start(mut commands: Commands, wallet: Res<Wallet>)
     );
     add_top_padding(&mut commands, root_ui, 25.0);
 
+    spawn_run_settings_texts(&mut commands, root_ui, *game_mode, &difficulty);
+    add_top_padding(&mut commands, root_ui, 25.0);
+
     let _button = commands.entity(root_ui).with_children(|parent| {
         parent
             .spawn((
@@ -86,3 +144,27 @@ pub fn spawn_sign_message_to_start(mut commands: Commands, wallet: Res<Wallet>)
             ));
     });
 }
+
+pub fn update_game_mode_text(
+    mut texts: Query<&mut Text, With<GameModeText>>,
+    game_mode: Res<GameMode>,
+) {
+    if !game_mode.is_changed() {
+        return;
+    }
+    for mut text in &mut texts {
+        text.0 = game_mode_text(*game_mode);
+    }
+}
+
+pub fn update_difficulty_text(
+    mut texts: Query<&mut Text, With<DifficultyText>>,
+    difficulty: Res<Difficulty>,
+) {
+    if !difficulty.is_changed() {
+        return;
+    }
+    for mut text in &mut texts {
+        text.0 = difficulty_text(&difficulty);
+    }
+}
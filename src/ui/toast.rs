@@ -0,0 +1,96 @@
+use bevy::{color::palettes::css::WHITE, prelude::*};
+
+use crate::solana::SolanaErrors;
+
+use super::*;
+
+pub const TOAST_DURATION_SECS: f32 = 4.0;
+pub const MAX_TOASTS: usize = 4;
+pub const TOAST_BACKGROUND_COLOR: Color = Color::srgba(0.55, 0.1, 0.1, 0.9);
+
+#[derive(Component)]
+pub struct ToastContainer;
+
+#[derive(Component)]
+pub struct Toast {
+    pub message: String,
+    pub timer: Timer,
+}
+
+// toasts are stacked in this container at the top right of the screen
+pub fn spawn_toast_container(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            width: Val::Px(320.0),
+            height: Val::Auto,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.0),
+            position_type: PositionType::Absolute,
+            right: Val::Percent(2.0),
+            top: Val::Percent(2.0),
+            ..default()
+        },
+        GlobalZIndex(3),
+        Name::new("toasts"),
+        ToastContainer,
+    ));
+}
+
+/// Turns the queued Solana errors into toasts, the same message is never shown twice at once
+pub fn show_error_toasts(
+    mut commands: Commands,
+    mut errors: ResMut<SolanaErrors>,
+    containers: Query<Entity, With<ToastContainer>>,
+    toasts: Query<&Toast>,
+) {
+    let Ok(container) = containers.get_single() else {
+        return;
+    };
+    let mut shown: Vec<String> = toasts.iter().map(|toast| toast.message.clone()).collect();
+
+    while let Some(err) = errors.pop_front() {
+        let message = err.user_message();
+        if shown.len() >= MAX_TOASTS || shown.contains(&message) {
+            continue;
+        }
+        shown.push(message.clone());
+
+        commands.entity(container).with_children(|p| {
+            p.spawn((
+                Node {
+                    padding: UiRect::all(Val::Px(10.0)),
+                    border: UiRect::all(Val::Px(3.0)),
+                    ..default()
+                },
+                BorderColor(BORDER_AND_TEXT_UI_COLOR),
+                BorderRadius::all(Val::Px(10.0)),
+                BackgroundColor(TOAST_BACKGROUND_COLOR),
+                Toast {
+                    message: message.clone(),
+                    timer: Timer::from_seconds(TOAST_DURATION_SECS, TimerMode::Once),
+                },
+            ))
+            .with_child((
+                Text::new(message),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                TextColor(WHITE.into()),
+            ));
+        });
+    }
+}
+
+pub fn despawn_expired_toasts(
+    mut commands: Commands,
+    mut toasts: Query<(Entity, &mut Toast)>,
+    time: Res<Time>,
+) {
+    for (entity, mut toast) in &mut toasts {
+        toast.timer.tick(time.delta());
+        if toast.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use bevy::prelude::*;
+use bevy::{
+    color::palettes::css::{BLACK, WHITE},
+    prelude::*,
+};
 
 use crate::{enemies::WaveControl, tower_building::SelectedTowerType};
 
@@ -9,8 +12,12 @@ pub enum SelectedTowerTextTypes {
     TowerSelected,
     TowerCost,
     TimeToBuild,
+    NextWaveBonus,
 }
 
+#[derive(Component)]
+pub struct NextWaveButton;
+
 // display a text to indicate the selected tower to buy/build
 pub fn spawn_tower_selected_text(mut commands: Commands) {
     let root_ui = commands
@@ -83,6 +90,46 @@ pub fn spawn_tower_selected_text(mut commands: Commands) {
         20.0,
         SelectedTowerTextTypes::TimeToBuild,
     );
+
+    // skips the time to build, the same as pressing Enter
+    commands.entity(root_ui).with_children(|parent| {
+        parent
+            .spawn((
+                Button,
+                NextWaveButton,
+                Node {
+                    width: Val::Px(220.0),
+                    height: Val::Px(45.0),
+                    border: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BorderColor(BLACK.into()),
+                BorderRadius::MAX,
+                BackgroundColor(WHITE.into()),
+            ))
+            .with_child((
+                Text::new("Next wave (Enter): +0 Gold"),
+                TextFont {
+                    font_size: 15.0,
+                    ..default()
+                },
+                TextColor(BLACK.into()),
+                SelectedTowerTextTypes::NextWaveBonus,
+            ));
+    });
+}
+
+pub fn handle_next_wave_btn(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<NextWaveButton>)>,
+    mut wave_control: ResMut<WaveControl>,
+) {
+    for interaction in &buttons {
+        if *interaction == Interaction::Pressed && wave_control.can_call_next_wave() {
+            wave_control.call_next_wave_early = true;
+        }
+    }
 }
 
 pub fn update_tower_selected_text(
@@ -104,6 +151,12 @@ pub fn update_tower_selected_text(
                     wave_control.time_between_waves.remaining_secs()
                 );
             }
+            SelectedTowerTextTypes::NextWaveBonus => {
+                text.0 = format!(
+                    "Next wave (Enter): +{} Gold",
+                    wave_control.early_call_bonus()
+                );
+            }
         }
     }
 }
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+use crate::solana::{active_cluster, TxAmount, TxHistory, TxStatus};
+
+use super::*;
+
+/// Amount of transactions listed per section, the full session is kept in the `TxHistory`
+pub const MAX_HISTORY_ENTRIES: usize = 6;
+
+#[derive(Component)]
+pub struct TxHistoryText;
+
+// history of the transactions of the current wallet, opened and closed with the H key
+pub fn toggle_tx_history_ui(
+    input: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    entities: Query<(Entity, &Name), With<Node>>,
+) {
+    if !input.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+
+    if let Some((entity, _)) = entities
+        .iter()
+        .find(|(_, name)| name.as_str() == "tx history")
+    {
+        commands.entity(entity).despawn_recursive();
+        return;
+    }
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Px(760.0),
+                height: Val::Auto,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                position_type: PositionType::Absolute,
+                left: Val::Percent(20.0),
+                border: UiRect::all(Val::Px(5.0)),
+                top: Val::Percent(20.0),
+                ..default()
+            },
+            BorderColor(BORDER_AND_TEXT_UI_COLOR),
+            BorderRadius::all(Val::Px(15.0)),
+            GlobalZIndex(2),
+            Name::new("tx history"),
+            BackgroundColor(BACKGROUND_COLOR),
+        ))
+        .with_child((
+            Text::new("Transaction History"),
+            TextFont {
+                font_size: 12.0,
+                ..default()
+            },
+            TextColor(BORDER_AND_TEXT_UI_COLOR),
+            TxHistoryText,
+        ));
+}
+
+pub fn update_tx_history_text(
+    mut texts: Query<&mut Text, With<TxHistoryText>>,
+    history: Res<TxHistory>,
+) {
+    let cluster = active_cluster();
+    for mut text in &mut texts {
+        let mut lines = vec![
+            format!("Transaction History - {:?} (H to close)", cluster),
+            String::new(),
+            "Sent this session:".to_string(),
+        ];
+        if history.session.is_empty() {
+            lines.push("  no transactions yet".to_string());
+        }
+        for tx in history.session.iter().rev().take(MAX_HISTORY_ENTRIES) {
+            let status = match &tx.status {
+                TxStatus::Sent => "sent".to_string(),
+                TxStatus::Confirmed => "confirmed".to_string(),
+                TxStatus::Finalized => "finalized".to_string(),
+                TxStatus::Failed(err) => format!("failed: {}", err.user_message()),
+            };
+            let amount = match tx.amount {
+                TxAmount::Nothing => String::new(),
+                TxAmount::Lamports(lamports) => {
+                    format!(" - {:.2} SOL", lamports as f64 / LAMPORTS_PER_SOL as f64)
+                }
+                TxAmount::Tokens(tokens) => format!(" - {} tokens", tokens),
+            };
+            lines.push(format!("  {}{} - {}", tx.action.label(), amount, status));
+            if let Some(signature) = tx.signature {
+                lines.push(format!(
+                    "    {}",
+                    cluster.explorer_url(&signature.to_string())
+                ));
+            }
+        }
+
+        lines.push(String::new());
+        lines.push("Recent player account signatures:".to_string());
+        if history.recent.is_empty() {
+            lines.push("  no signatures found".to_string());
+        }
+        for recent in history.recent.iter().take(MAX_HISTORY_ENTRIES) {
+            lines.push(format!(
+                "  slot {}{} - {}",
+                recent.slot,
+                if recent.failed { " (failed)" } else { "" },
+                cluster.explorer_url(&recent.signature)
+            ));
+        }
+
+        text.0 = lines.join("\n");
+    }
+}
@@ -0,0 +1,118 @@
+use bevy::{
+    color::palettes::css::{BLACK, WHITE},
+    prelude::*,
+};
+
+use crate::{
+    enemies::{GameMode, WaveControl},
+    solana::GoldLedger,
+    tower_building::{Difficulty, GameRng, RunStats},
+};
+
+use super::*;
+
+// shown when the last wave of the campaign is cleared
+pub fn spawn_victory_ui(
+    mut commands: Commands,
+    ledger: Res<GoldLedger>,
+    wave_control: Res<WaveControl>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    stats: Res<RunStats>,
+    game_rng: Res<GameRng>,
+) {
+    let root_ui = commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            Name::new("victory"),
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+        ))
+        .id();
+
+    let create_text = |commands: &mut Commands, text: &str, font_size: f32| {
+        commands.entity(root_ui).with_children(|p| {
+            p.spawn((
+                Text::new(text),
+                TextFont {
+                    font_size,
+                    ..default()
+                },
+                TextColor(WHITE.into()),
+            ));
+        });
+    };
+
+    let add_top_padding = |commands: &mut Commands, parent: Entity, px: f32| {
+        commands.entity(parent).with_children(|p| {
+            p.spawn(Node {
+                height: Val::Px(px),
+                ..default()
+            });
+        });
+    };
+
+    let _header = create_text(&mut commands, "Victory", 35.0);
+    add_top_padding(&mut commands, root_ui, 25.0);
+
+    let _message = create_text(
+        &mut commands,
+        &format!(
+            "You held the line through all {} waves.",
+            wave_control.waves.len()
+        ),
+        15.0,
+    );
+    add_top_padding(&mut commands, root_ui, 25.0);
+
+    spawn_run_summary(&mut commands, root_ui, &stats, game_rng.seed);
+    add_top_padding(&mut commands, root_ui, 25.0);
+
+    spawn_run_settings_texts(&mut commands, root_ui, *game_mode, &difficulty);
+    add_top_padding(&mut commands, root_ui, 25.0);
+
+    if ledger.enabled {
+        let _withdraw_message = create_text(
+            &mut commands,
+            &format!(
+                "Press G to request the {} gold earned in this run as tokens, the game doesn't pay it.",
+                ledger.earned_in_run
+            ),
+            15.0,
+        );
+        add_top_padding(&mut commands, root_ui, 25.0);
+    }
+
+    let _button = commands.entity(root_ui).with_children(|parent| {
+        parent
+            .spawn((
+                Button,
+                Node {
+                    width: Val::Px(150.0),
+                    height: Val::Px(65.0),
+                    border: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BorderColor(BLACK.into()),
+                BorderRadius::MAX,
+                BackgroundColor(Color::Srgba(Srgba::new(1.0, 1.0, 1.0, 0.5))),
+            ))
+            .with_child((
+                Text::new("Play again"),
+                TextFont {
+                    font_size: 23.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.0, 0.0, 0.0)),
+            ));
+    });
+}
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use crate::{
+    enemies::{EnemyType, GameMode, Movement, WaveControl},
+    tower_building::Difficulty,
+};
+
+use super::*;
+
+#[derive(Component)]
+pub struct WavePreviewText;
+
+/// One line per enemy type of the next wave, with the life and speed it spawns with. Groups of
+/// the same enemy with the same life are counted together.
+pub fn wave_preview_lines(
+    wave_control: &WaveControl,
+    game_mode: GameMode,
+    difficulty: &Difficulty,
+) -> Vec<String> {
+    let wave_count = wave_control.next_wave_count();
+    let Some(wave) = wave_control.next_wave(game_mode) else {
+        return vec!["No more waves".to_string()];
+    };
+
+    // same scaling as `spawn_enemy`
+    let mut entries: Vec<(&EnemyType, f32, u16, u16)> = Vec::new();
+    for group in &wave.groups {
+        let Some(enemy_type) = wave_control.enemy_type(&group.enemy) else {
+            continue;
+        };
+        let life_scale = difficulty.life_scale(wave_count) * group.health_multiplier;
+        let life = (enemy_type.base_life as f32 * life_scale).round() as u16;
+        match entries.iter_mut().find(|(entry_type, _, entry_life, _)| {
+            entry_type.id == enemy_type.id && *entry_life == life
+        }) {
+            Some((_, _, _, count)) => *count += group.count,
+            None => entries.push((enemy_type, life_scale, life, group.count)),
+        }
+    }
+
+    let mut lines = vec![format!("Next wave: {}", wave_count.saturating_add(1))];
+    for (enemy_type, life_scale, life, count) in entries {
+        let speed = difficulty.enemy_speed(enemy_type.base_speed, wave_count);
+        lines.push(format!(
+            "{}x {}: {} life, {:.0} speed",
+            count, enemy_type.id, life, speed
+        ));
+
+        let mut traits: Vec<String> = enemy_type
+            .abilities
+            .iter()
+            .map(|ability| ability.describe(life_scale))
+            .collect();
+        if enemy_type.boss.is_some() {
+            traits.insert(0, "boss".to_string());
+        }
+        if enemy_type.movement == Movement::Flying {
+            traits.push("flying".to_string());
+        }
+        for immunity in &enemy_type.immunities {
+            traits.push(format!("immune to {:?}", immunity));
+        }
+        if !traits.is_empty() {
+            lines.push(format!("  {}", traits.join(", ")));
+        }
+    }
+    lines
+}
+
+// enemies of the next wave, shown while building
+pub fn spawn_wave_preview_ui(
+    mut commands: Commands,
+    wave_control: Res<WaveControl>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Px(320.0),
+                height: Val::Auto,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                position_type: PositionType::Absolute,
+                left: Val::Percent(3.0),
+                border: UiRect::all(Val::Px(5.0)),
+                top: Val::Percent(5.0),
+                ..default()
+            },
+            BorderColor(BORDER_AND_TEXT_UI_COLOR),
+            BorderRadius::all(Val::Px(15.0)),
+            Name::new("wave_preview_root_node"),
+            BackgroundColor(BACKGROUND_COLOR),
+        ))
+        .with_child((
+            Text::new(wave_preview_lines(&wave_control, *game_mode, &difficulty).join("\n")),
+            TextFont {
+                font_size: 15.0,
+                ..default()
+            },
+            TextColor(BORDER_AND_TEXT_UI_COLOR),
+            WavePreviewText,
+        ));
+}
+
+// the enemy definitions and wave script are hot reloaded, so the preview follows them
+pub fn update_wave_preview_text(
+    mut texts: Query<&mut Text, With<WavePreviewText>>,
+    wave_control: Res<WaveControl>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+) {
+    for mut text in &mut texts {
+        text.0 = wave_preview_lines(&wave_control, *game_mode, &difficulty).join("\n");
+    }
+}
+
+pub fn despawn_wave_preview_ui(
+    entities: Query<(Entity, &Name), With<Node>>,
+    mut commands: Commands,
+) {
+    for (entity, name) in &entities {
+        if name.as_str() == "wave_preview_root_node" {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}